@@ -0,0 +1,46 @@
+use std::time::Duration;
+
+use harmony::{client::RevoltClient, models::id::ChannelId};
+
+#[tokio::main]
+async fn main() {
+    let token = std::env::var("REVOLT_TOKEN").expect("Expected Revolt token in environment");
+    let channel_id: ChannelId = std::env::var("REVOLT_CHANNEL")
+        .expect("Expected a channel id in environment")
+        .parse()
+        .expect("Expected REVOLT_CHANNEL to be a valid channel id");
+
+    let mut client = RevoltClient::builder()
+        .with_token(&token)
+        .with_metrics_endpoint("127.0.0.1:9100")
+        .build()
+        .await
+        .unwrap();
+
+    client.login().await.unwrap();
+
+    // post how many messages are cached every minute
+    let handle = client.handle();
+    tokio::spawn(async move {
+        loop {
+            tokio::time::sleep(Duration::from_secs(60)).await;
+
+            let cached = handle.cache().await.messages.len();
+
+            if let Err(err) = handle.say(&channel_id, &format!("{cached} messages cached")).await {
+                println!("Could not send message: {err:#?}");
+            }
+        }
+    });
+
+    // shut down cleanly on ctrl+c
+    let handle = client.handle();
+    tokio::spawn(async move {
+        let _ = tokio::signal::ctrl_c().await;
+        handle.shutdown();
+    });
+
+    if let Err(e) = client.listen().await {
+        println!("Error: {e:#?}");
+    }
+}
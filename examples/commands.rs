@@ -0,0 +1,94 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use harmony::{client::{context::Context, RevoltClient}, framework::{StandardFramework, args::Args, command::{Command, CommandGroup, CommandHandler, CommandResult}, bucket::{Bucket, BucketType}, checks::{Check, CheckKind}}, models::{message::Message, Permission}};
+
+pub struct Ping;
+
+#[async_trait]
+impl CommandHandler for Ping {
+    async fn run(&self, ctx: &mut Context, msg: &Message, _args: Args) -> CommandResult {
+        ctx.http.say(&msg.channel, "Pong!").await?;
+
+        Ok(())
+    }
+}
+
+pub struct Add;
+
+#[async_trait]
+impl CommandHandler for Add {
+    async fn run(&self, ctx: &mut Context, msg: &Message, mut args: Args) -> CommandResult {
+        let a = args.single::<i64>()?;
+        let b = args.single::<i64>()?;
+
+        ctx.http.say(&msg.channel, &format!("{a} + {b} = {}", a + b)).await?;
+
+        Ok(())
+    }
+}
+
+pub struct Avatar;
+
+#[async_trait]
+impl CommandHandler for Avatar {
+    async fn run(&self, ctx: &mut Context, msg: &Message, mut args: Args) -> CommandResult {
+        let user = if args.is_empty() {
+            ctx.user(&msg.author).await?
+        } else {
+            args.user(ctx).await?
+        };
+
+        let reply = match user.avatar {
+            Some(avatar) => format!("{}'s avatar id is `{}`", user.username, avatar.id),
+            None => format!("{} has no avatar", user.username),
+        };
+
+        ctx.http.say(&msg.channel, &reply).await?;
+
+        Ok(())
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let token = std::env::var("REVOLT_TOKEN").expect("Expected Revolt token in environment");
+
+    let general = CommandGroup::new("General")
+        .with_command(Command::new("ping", Ping)
+            .with_alias("p")
+            .with_description("Replies with pong")
+            .with_bucket(Bucket::cooldown(BucketType::User, Duration::from_secs(5))))
+        .with_command(Command::new("avatar", Avatar)
+            .with_description("Shows a user's avatar")
+            .with_usage("[user]"));
+
+    let math = CommandGroup::new("Math")
+        .with_prefix("math")
+        .with_check(Check::ServerOnly)
+        .with_check(Check::RequirePermissions(vec![Permission::SendMessage]))
+        .with_command(Command::new("add", Add)
+            .with_description("Adds two numbers together")
+            .with_usage("<a> <b>"));
+
+    let framework = StandardFramework::new()
+        .with_prefix("!")
+        .with_mention_prefix(true)
+        .with_check_reply(CheckKind::Cooldown, "Slow down! Try again in {remaining}s")
+        .with_group(general)
+        .with_group(math);
+
+    let mut client = RevoltClient::builder()
+        .with_token(&token)
+        .with_packet_format(harmony::websocket::PacketFormat::Json)
+        .with_framework(framework)
+        .build()
+        .await
+        .unwrap();
+
+    client.login().await.unwrap();
+
+    if let Err(e) = client.listen().await {
+        println!("Error: {e:#?}");
+    }
+}
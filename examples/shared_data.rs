@@ -0,0 +1,49 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use async_trait::async_trait;
+use harmony::{client::{event_handler::EventHandler, context::Context, RevoltClient}, models::message::Message};
+
+/// Counts how many times `!ping` has been used
+#[derive(Default)]
+pub struct PingCounter(AtomicUsize);
+
+pub struct Handler;
+
+#[async_trait]
+impl EventHandler for Handler {
+    async fn message_received(&self, ctx: &mut Context, msg: Message) {
+        if msg.content.as_deref() != Some("!ping") {
+            return;
+        }
+
+        let count = {
+            let data = ctx.data.read().await;
+            let counter = data.get::<PingCounter>().expect("PingCounter is added in main");
+
+            counter.0.fetch_add(1, Ordering::Relaxed) + 1
+        };
+
+        if let Err(err) = ctx.http.say(&msg.channel, &format!("Pong! (#{count})")).await {
+            println!("Could not send message: {err:#?}");
+        }
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    let token = std::env::var("REVOLT_TOKEN").expect("Expected Revolt token in environment");
+
+    let mut client = RevoltClient::builder()
+        .with_token(&token)
+        .with_event_handler(Handler)
+        .with_data(PingCounter::default())
+        .build()
+        .await
+        .unwrap();
+
+    client.login().await.unwrap();
+
+    if let Err(e) = client.listen().await {
+        println!("Error: {e:#?}");
+    }
+}
@@ -1,6 +1,9 @@
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, sync::Arc};
 
-use crate::models::{user::User, channel::Channel, server::Server, message::{Message, PartialMessage}};
+use tokio::sync::RwLock;
+
+use crate::http::prelude::HttpClient;
+use crate::models::{user::User, channel::Channel, server::Server, message::{Message, MessageUpdateData}, member::Member, id::{ChannelId, MessageId, ServerId, UserId}};
 
 /*type Result<T> = std::result::Result<T, CacheError>;
 
@@ -11,93 +14,242 @@ pub enum CacheError {
 // TODO: add limits so these dont balloon in size ?
 #[derive(Debug, Clone, PartialEq)]
 pub struct Cache {
-    pub users: BTreeMap<String, User>,
-    pub channels: BTreeMap<String, Channel>,
-    pub servers: BTreeMap<String, Server>,
-    pub messages: BTreeMap<String, Message>,
+    pub users: BTreeMap<UserId, User>,
+    pub channels: BTreeMap<ChannelId, Channel>,
+    pub servers: BTreeMap<ServerId, Server>,
+    pub messages: BTreeMap<MessageId, Message>,
+    /// Server members, keyed by (server id, user id)
+    pub members: BTreeMap<(ServerId, UserId), Member>,
+    /// Previous versions of edited messages, oldest first
+    pub message_history: BTreeMap<MessageId, Vec<Message>>,
 
     pub max_messages: usize,
+    /// How many previous versions are kept per message, `0` disables edit history
+    pub max_revisions: usize,
 }
 
 impl Cache {
-    pub fn new(max_messages: usize) -> Self {
+    pub fn new(max_messages: usize, max_revisions: usize) -> Self {
         Self {
             users: BTreeMap::new(),
             channels: BTreeMap::new(),
             servers: BTreeMap::new(),
             messages: BTreeMap::new(),
+            members: BTreeMap::new(),
+            message_history: BTreeMap::new(),
             max_messages,
+            max_revisions,
         }
     }
 
-    pub fn get_user(&self, id: &str) -> Option<User> {
-        if let Some(user) = self.users.get(id) {
-            Some(user.clone())
-        } else {
-            None
-        }
+    /// Creates an empty cache with the same limits as this one
+    pub fn empty_copy(&self) -> Self {
+        Self::new(self.max_messages, self.max_revisions)
+    }
+
+    pub fn get_user(&self, id: &UserId) -> Option<User> {
+        self.users.get(id).cloned()
+    }
+
+    pub fn get_channel(&self, id: &ChannelId) -> Option<Channel> {
+        self.channels.get(id).cloned()
+    }
+
+    /// Finds the DM channel with a user
+    pub fn get_dm_channel(&self, user_id: &UserId) -> Option<Channel> {
+        self.channels.values()
+            .find(|channel| matches!(channel, Channel::DirectMessage(dm) if dm.get_recipients().contains(user_id)))
+            .cloned()
     }
 
-    pub fn get_channel(&self, id: &str) -> Option<Channel> {
-        if let Some(channel) = self.channels.get(id) {
-            Some(channel.clone())
-        } else {
-            None
+    pub fn get_server(&self, id: &ServerId) -> Option<Server> {
+        self.servers.get(id).cloned()
+    }
+
+    pub fn get_message(&self, id: &MessageId) -> Option<Message> {
+        self.messages.get(id).cloned()
+    }
+
+    pub fn get_member(&self, server_id: &ServerId, user_id: &UserId) -> Option<Member> {
+        self.members.get(&(server_id.clone(), user_id.clone())).cloned()
+    }
+
+    /// Gets every cached member of a server
+    pub fn get_server_members(&self, server_id: &ServerId) -> Vec<Member> {
+        self.members.values()
+            .filter(|member| &member.id.server == server_id)
+            .cloned()
+            .collect()
+    }
+
+    pub fn add_member(&mut self, member: Member) {
+        self.members.insert((member.id.server.clone(), member.id.user.clone()), member);
+    }
+
+    pub fn remove_member(&mut self, server_id: &ServerId, user_id: &UserId) -> Option<Member> {
+        self.members.remove(&(server_id.clone(), user_id.clone()))
+    }
+
+    /// Resolves the name shown for a user, using their nickname in `server_id` if they have one,
+    /// then their display name and finally their username. Returns `None` if the user isn't cached
+    pub fn get_display_name(&self, server_id: Option<&ServerId>, user_id: &UserId) -> Option<String> {
+        let user = self.users.get(user_id)?;
+
+        let member = server_id.and_then(|server_id| {
+            self.members.get(&(server_id.clone(), user_id.clone()))
+        });
+
+        match member {
+            Some(member) => Some(member.display_name(user)),
+            None => Some(user.get_display_name()),
         }
     }
 
-    pub fn get_server(&self, id: &str) -> Option<Server> {
-        if let Some(server) = self.servers.get(id) {
-            Some(server.clone())
-        } else {
-            None
+    /// Gets the previous versions of a message, oldest first
+    pub fn get_message_history(&self, id: &MessageId) -> Vec<Message> {
+        self.message_history.get(id).cloned().unwrap_or_default()
+    }
+
+    /// Stores a previous version of a message, dropping the oldest
+    /// versions once there are more than `max_revisions`
+    pub fn add_revision(&mut self, old_msg: Message) {
+        if self.max_revisions == 0 {
+            return;
+        }
+
+        let history = self.message_history.entry(old_msg.id.clone()).or_default();
+
+        history.push(old_msg);
+
+        if history.len() > self.max_revisions {
+            let excess = history.len() - self.max_revisions;
+            history.drain(..excess);
         }
     }
 
-    pub fn get_message(&self, id: &str) -> Option<Message> {
-        if let Some(message) = self.messages.get(id) {
-            Some(message.clone())
-        } else {
-            None
+    /// Removes a server along with its channels, their messages and the server's members,
+    /// returning the removed server
+    pub fn remove_server(&mut self, server_id: &ServerId) -> Option<Server> {
+        let server = self.servers.remove(server_id);
+
+        let channel_ids = self.channels.values()
+            .filter(|channel| channel.get_server_id().as_ref() == Some(server_id))
+            .map(|channel| channel.get_id())
+            .chain(server.iter().flat_map(|server| server.channels.clone()))
+            .collect::<Vec<_>>();
+
+        for channel_id in &channel_ids {
+            self.channels.remove(channel_id);
         }
+
+        self.messages.retain(|_, msg| !channel_ids.contains(&msg.channel));
+        self.members.retain(|(member_server_id, _), _| member_server_id != server_id);
+        self.message_history.retain(|_, history| {
+            history.first().is_some_and(|msg| !channel_ids.contains(&msg.channel))
+        });
+
+        server
+    }
+
+    pub fn remove_message(&mut self, msg_id: &MessageId) -> Option<Message> {
+        self.message_history.remove(msg_id);
+        self.messages.remove(msg_id)
     }
 
     pub fn add_message(&mut self, msg: Message) {
-        if self.messages.len() >= self.max_messages {
-            let _ = self.messages.pop_first();
+        if self.messages.len() >= self.max_messages && !self.messages.contains_key(&msg.id) {
+            if let Some((evicted_id, _)) = self.messages.pop_first() {
+                self.message_history.remove(&evicted_id);
+            }
         }
     
         self.messages.insert(msg.clone().id, msg);
     }
 
-    pub fn update_message(&mut self, msg_id: &str, partial_msg: PartialMessage) {
-        if self.messages.contains_key(msg_id) {
-            self.messages.entry(msg_id.to_string())
-                .and_modify(|msg| {
-                    let mut new_msg = msg.clone();
+    pub fn update_message(&mut self, msg_id: &MessageId, data: MessageUpdateData) {
+        if let Some(msg) = self.messages.get_mut(msg_id) {
+            msg.apply(data);
+        }
+    }
+}
 
-                    if let Some(content) = partial_msg.content {
-                        new_msg.content = Some(content);
-                    }
+/// Something holding an [`HttpClient`] and possibly the client's shared cache,
+/// so helpers work from a `Context`, a `ClientHandle` or an `HttpClient` alike
+pub trait CacheHttp: AsRef<HttpClient> + Sync {
+    /// The cache shared with the running client, `None` when there is no client
+    fn shared_cache(&self) -> Option<&Arc<RwLock<Cache>>> {
+        None
+    }
+}
 
-                    if let Some(embeds) = partial_msg.embeds {
-                        new_msg.embeds = embeds;
-                    }
-                    
-                    if let Some(attachments) = partial_msg.attachments {
-                        new_msg.attachments = attachments;
-                    };
+impl CacheHttp for HttpClient {}
 
-                    if let Some(replies) = partial_msg.replies {
-                        new_msg.replies = replies;
-                    }
+#[cfg(test)]
+mod tests {
+    use crate::models::{channel::Channel, message::Message};
 
-                    if let Some(masquerade) = partial_msg.masquerade {
-                        new_msg.masquerade = Some(masquerade);
-                    }
+    use super::Cache;
+
+    fn message(content: &str) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "_id": "01GXYZ0000000000000000000A",
+            "channel": "01GXYZ0000000000000000000B",
+            "author": "01GXYZ0000000000000000000C",
+            "content": content,
+        })).unwrap()
+    }
 
-                    *msg = new_msg
-                });
+    #[test]
+    fn keeps_the_latest_revisions() {
+        let mut cache = Cache::new(16, 2);
+
+        for content in ["one", "two", "three"] {
+            cache.add_revision(message(content));
         }
+
+        let history = cache.get_message_history(&message("").id)
+            .into_iter()
+            .map(|msg| msg.content.unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(history, ["two", "three"]);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn revisions_can_be_disabled() {
+        let mut cache = Cache::new(16, 0);
+
+        cache.add_revision(message("one"));
+
+        assert!(cache.message_history.is_empty());
+    }
+
+    #[test]
+    fn evicting_a_message_drops_its_history() {
+        let mut cache = Cache::new(1, 2);
+        let msg = message("one");
+
+        cache.add_message(msg.clone());
+        cache.add_revision(msg.clone());
+
+        let mut other = message("two");
+        other.id = "01GXYZ0000000000000000000D".parse().unwrap();
+        cache.add_message(other);
+
+        assert!(cache.get_message(&msg.id).is_none());
+        assert!(cache.get_message_history(&msg.id).is_empty());
+    }
+
+    #[test]
+    fn finds_dm_channels_by_recipient() {
+        let mut cache = Cache::new(16, 0);
+        let dm: Channel = serde_json::from_str(include_str!("../../tests/fixtures/dm_channel.json")).unwrap();
+        let text: Channel = serde_json::from_str(include_str!("../../tests/fixtures/text_channel.json")).unwrap();
+
+        cache.channels.insert(dm.get_id(), dm.clone());
+        cache.channels.insert(text.get_id(), text);
+
+        assert_eq!(cache.get_dm_channel(&"01FD58YK5W7QRV5H3D64NTEB4K".parse().unwrap()), Some(dm));
+        assert_eq!(cache.get_dm_channel(&"01GXYZ0000000000000000000C".parse().unwrap()), None);
+    }
+}
@@ -1,21 +1,41 @@
 use std::sync::Arc;
 
-use crate::{websocket::{self, ClientConfigBuilder, PacketFormat, SocketClient}, http::prelude::{ClientSessionType, HttpClientBuilderError, HttpClientBuilder}, cache::Cache};
+use crate::{websocket::{self, ClientConfigBuilder, PacketFormat, SocketClient, SocketError}, http::prelude::{ClientSessionType, HttpClientBuilderError, HttpClientBuilder, HttpError}, cache::Cache, framework::StandardFramework, metrics::{Metrics, exporter}};
 
-use super::{event_handler::EventHandler, RevoltClient};
+use tokio::sync::{Notify, RwLock};
 
-#[derive(Debug)]
+use super::{event_handler::EventHandler, RevoltClient, data::TypeMap};
+
+/// Represents an error that occurred while building a `RevoltClient`
+#[derive(Debug, thiserror::Error)]
 pub enum RevoltBuilderError {
-    HttpClientError(HttpClientBuilderError)
+    #[error("Could not build the HTTP client: {0}")]
+    HttpClientError(#[from] HttpClientBuilderError),
+    #[error("Could not query the Revolt node: {0}")]
+    NodeQueryError(#[source] HttpError),
+    #[error("Could not connect to the websocket: {0}")]
+    SocketError(#[from] SocketError),
+    #[error("Could not serve metrics: {0}")]
+    MetricsError(#[source] std::io::Error),
 }
 
 pub struct RevoltClientBuilder {
     http: HttpClientBuilder,
     socket_config: websocket::ClientConfigBuilder,
     event_handler: Option<Arc<dyn EventHandler>>,
+    framework: Option<Arc<StandardFramework>>,
+    data: TypeMap,
+    metrics_addr: Option<String>,
     token: Option<String>,
 
     max_messages: usize,
+    max_revisions: usize,
+}
+
+impl Default for RevoltClientBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl RevoltClientBuilder {
@@ -24,8 +44,12 @@ impl RevoltClientBuilder {
             http: HttpClientBuilder::new(),
             socket_config: ClientConfigBuilder::new(),
             event_handler: None,
+            framework: None,
+            data: TypeMap::new(),
+            metrics_addr: None,
             token: None,
             max_messages: 256,
+            max_revisions: 0,
         }
     }
 
@@ -35,12 +59,42 @@ impl RevoltClientBuilder {
         self
     }
 
+    /// Keeps up to `max_revisions` previous versions of each cached message when it's edited,
+    /// available through `Cache::get_message_history`. Disabled by default
+    pub fn set_max_message_revisions(mut self, max_revisions: usize) -> Self {
+        self.max_revisions = max_revisions;
+
+        self
+    }
+
     pub fn with_event_handler<T: EventHandler + 'static>(mut self, event_handler: T) -> Self {
         self.event_handler = Some(Arc::new(event_handler));
 
         self
     }
 
+    pub fn with_framework(mut self, framework: StandardFramework) -> Self {
+        self.framework = Some(Arc::new(framework));
+
+        self
+    }
+
+    /// Adds a value to the client's shared data, accessible from handlers
+    /// with `ctx.data.read().await.get::<T>()`
+    pub fn with_data<T: Send + Sync + 'static>(mut self, value: T) -> Self {
+        self.data.insert(value);
+
+        self
+    }
+
+    /// Serves the client's metrics in Prometheus' text format on `addr`, e.g. `127.0.0.1:9100`
+    /// until `RevoltClient::listen` returns
+    pub fn with_metrics_endpoint(mut self, addr: &str) -> Self {
+        self.metrics_addr = Some(addr.to_string());
+
+        self
+    }
+
     pub fn with_api(mut self, api_url: &str) -> Self {
         self.http = self.http.with_api(api_url);
 
@@ -79,25 +133,40 @@ impl RevoltClientBuilder {
     }
 
     pub async fn build(self) -> Result<RevoltClient, RevoltBuilderError> {
-        let http = match self.http.build().await {
-            Ok(http) => http,
-            Err(e) => {
-                return Err(RevoltBuilderError::HttpClientError(e));
-            }
-        };
+        let metrics = Arc::new(Metrics::new());
+
+        let http = self.http.with_metrics(metrics.clone()).build().await?;
 
-        let build = http.query_node().await.unwrap();
+        let build = http.query_node().await
+            .map_err(RevoltBuilderError::NodeQueryError)?;
 
         let socket_config = self.socket_config.with_websocket_url(&build.ws).build();
 
-        let socket = SocketClient::connect(socket_config).await;
+        let socket = SocketClient::connect(socket_config).await?;
+
+        metrics.record_connection();
+
+        let metrics_exporter = match self.metrics_addr {
+            Some(addr) => {
+                let exporter = exporter::serve(metrics.clone(), addr).await
+                    .map_err(RevoltBuilderError::MetricsError)?;
+
+                Some(Arc::new(exporter))
+            },
+            None => None,
+        };
 
         Ok(RevoltClient {
-            cache: Cache::new(self.max_messages),
+            cache: Arc::new(RwLock::new(Cache::new(self.max_messages, self.max_revisions))),
             http,
             socket,
             event_handler: self.event_handler,
-            token: self.token
+            framework: self.framework,
+            data: Arc::new(RwLock::new(self.data)),
+            metrics,
+            metrics_exporter,
+            token: self.token,
+            shutdown: Arc::new(Notify::new()),
         })
     }
 }
\ No newline at end of file
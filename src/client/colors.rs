@@ -1,63 +1,610 @@
-use std::fmt::Display;
+use std::{fmt::Display, str::FromStr};
 
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// The maximum length of a colour accepted by Revolt
+pub const MAX_COLOUR_LENGTH: usize = 128;
+
+/// Every named colour defined by CSS
+pub const NAMED_COLOURS: [&str; 150] = [
+    "aliceblue", "antiquewhite", "aqua", "aquamarine", "azure", "beige", "bisque", "black",
+    "blanchedalmond", "blue", "blueviolet", "brown", "burlywood", "cadetblue", "chartreuse",
+    "chocolate", "coral", "cornflowerblue", "cornsilk", "crimson", "cyan", "darkblue", "darkcyan",
+    "darkgoldenrod", "darkgray", "darkgreen", "darkgrey", "darkkhaki", "darkmagenta",
+    "darkolivegreen", "darkorange", "darkorchid", "darkred", "darksalmon", "darkseagreen",
+    "darkslateblue", "darkslategray", "darkslategrey", "darkturquoise", "darkviolet", "deeppink",
+    "deepskyblue", "dimgray", "dimgrey", "dodgerblue", "firebrick", "floralwhite", "forestgreen",
+    "fuchsia", "gainsboro", "ghostwhite", "gold", "goldenrod", "gray", "green", "greenyellow",
+    "grey", "honeydew", "hotpink", "indianred", "indigo", "ivory", "khaki", "lavender",
+    "lavenderblush", "lawngreen", "lemonchiffon", "lightblue", "lightcoral", "lightcyan",
+    "lightgoldenrodyellow", "lightgray", "lightgreen", "lightgrey", "lightpink", "lightsalmon",
+    "lightseagreen", "lightskyblue", "lightslategray", "lightslategrey", "lightsteelblue",
+    "lightyellow", "lime", "limegreen", "linen", "magenta", "maroon", "mediumaquamarine",
+    "mediumblue", "mediumorchid", "mediumpurple", "mediumseagreen", "mediumslateblue",
+    "mediumspringgreen", "mediumturquoise", "mediumvioletred", "midnightblue", "mintcream",
+    "mistyrose", "moccasin", "navajowhite", "navy", "oldlace", "olive", "olivedrab", "orange",
+    "orangered", "orchid", "palegoldenrod", "palegreen", "paleturquoise", "palevioletred",
+    "papayawhip", "peachpuff", "peru", "pink", "plum", "powderblue", "purple", "rebeccapurple",
+    "red", "rosybrown", "royalblue", "saddlebrown", "salmon", "sandybrown", "seagreen", "seashell",
+    "sienna", "silver", "skyblue", "slateblue", "slategray", "slategrey", "snow", "springgreen",
+    "steelblue", "tan", "teal", "thistle", "tomato", "turquoise", "violet", "wheat", "white",
+    "whitesmoke", "yellow", "yellowgreen", "transparent", "currentcolor",
+];
+
+/// Represents a colour that Revolt wouldn't accept
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ColourError {
+    #[error("`{0}` is not a valid colour")]
+    Invalid(String),
+    #[error("The colour is too long, the maximum is {max} characters")]
+    TooLong { max: usize },
+    #[error("A gradient needs at least two colour stops")]
+    NotEnoughStops,
+    #[error("Gradients can't be nested")]
+    NestedGradient,
+    #[error("The {0} is out of range")]
+    OutOfRange(&'static str),
+}
+
+/// Any colour Revolt accepts for roles, masquerades and embeds.
+///
+/// Parse one with `"#ff0000".parse::<Colour>()`, or convert
+/// any of the types in this module with `.into()`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Colour {
+    Hex(Hex),
+    Rgb(Rgb),
+    Rgba(Rgba),
+    Hsl(Hsl),
+    Hsla(Hsla),
+    /// A named CSS colour, e.g. `rebeccapurple`
+    Named(String),
+    /// A CSS variable of Revolt's theme, e.g. `var(--accent)`
+    Variable(String),
+    LinearGradient(LinearGradient),
+    RadialGradient(RadialGradient),
+    ConicGradient(ConicGradient),
+    /// A colour received from Revolt, kept exactly as it was written so it's sent back unchanged.
+    /// Used when the colour couldn't be parsed, or is written differently than this module
+    /// would write it (e.g. `#fff`). Never produced by `FromStr`, see [`Colour::normalize`]
+    Raw(String),
+}
+
+impl Colour {
+    /// Checks the colour's values are in range and that it fits Revolt's length limit
+    pub fn validate(&self) -> Result<(), ColourError> {
+        match self {
+            Self::Hsl(hsl) => hsl.validate()?,
+            Self::Hsla(hsla) => hsla.validate()?,
+            Self::Rgba(rgba) => validate_alpha(rgba.a)?,
+            Self::Named(name) => {
+                if !NAMED_COLOURS.contains(&name.as_str()) {
+                    return Err(ColourError::Invalid(name.clone()));
+                }
+            },
+            Self::Variable(name) => {
+                if name.is_empty() || !name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-') {
+                    return Err(ColourError::Invalid(self.to_string()));
+                }
+            },
+            Self::LinearGradient(gradient) => validate_stops(&gradient.stops)?,
+            Self::RadialGradient(gradient) => {
+                if gradient.shape_position > 100 {
+                    return Err(ColourError::OutOfRange("shape position"));
+                }
+
+                validate_stops(&gradient.stops)?
+            },
+            Self::ConicGradient(gradient) => validate_stops(&gradient.stops)?,
+            Self::Raw(raw) => return raw.parse::<Colour>().map(|_| ()),
+            Self::Hex(_) | Self::Rgb(_) => (),
+        }
+
+        if self.to_string().len() > MAX_COLOUR_LENGTH {
+            return Err(ColourError::TooLong { max: MAX_COLOUR_LENGTH });
+        }
+
+        Ok(())
+    }
+
+    /// Parses a [`Colour::Raw`] colour, returning any other colour as is
+    pub fn normalize(&self) -> Result<Colour, ColourError> {
+        match self {
+            Self::Raw(raw) => raw.parse(),
+            colour => Ok(colour.clone()),
+        }
+    }
+
+    fn is_gradient(&self) -> bool {
+        matches!(self, Self::LinearGradient(_) | Self::RadialGradient(_) | Self::ConicGradient(_))
+    }
+}
+
+impl Display for Colour {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Hex(hex) => hex.fmt(f),
+            Self::Rgb(rgb) => rgb.fmt(f),
+            Self::Rgba(rgba) => rgba.fmt(f),
+            Self::Hsl(hsl) => hsl.fmt(f),
+            Self::Hsla(hsla) => hsla.fmt(f),
+            Self::Named(name) => write!(f, "{name}"),
+            Self::Variable(name) => write!(f, "var(--{name})"),
+            Self::LinearGradient(gradient) => gradient.fmt(f),
+            Self::RadialGradient(gradient) => gradient.fmt(f),
+            Self::ConicGradient(gradient) => gradient.fmt(f),
+            Self::Raw(raw) => write!(f, "{raw}"),
+        }
+    }
+}
+
+impl FromStr for Colour {
+    type Err = ColourError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() > MAX_COLOUR_LENGTH {
+            return Err(ColourError::TooLong { max: MAX_COLOUR_LENGTH });
+        }
+
+        let colour = parse_colour(&s.trim().to_lowercase())
+            .ok_or_else(|| ColourError::Invalid(s.to_string()))?;
+
+        colour.validate()?;
+
+        Ok(colour)
+    }
+}
+
+impl Serialize for Colour {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Colour {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = String::deserialize(deserializer)?;
+
+        // Revolt's own validation is looser than ours, so a colour it stored
+        // shouldn't make the whole object fail to deserialize, and a colour
+        // written differently than we'd write it is kept as written
+        match raw.parse::<Colour>() {
+            Ok(colour) if colour.to_string() == raw => Ok(colour),
+            _ => Ok(Self::Raw(raw)),
+        }
+    }
+}
+
+macro_rules! impl_into_colour {
+    ($($ty:ident),*) => {
+        $(
+            impl From<$ty> for Colour {
+                fn from(colour: $ty) -> Self {
+                    Self::$ty(colour)
+                }
+            }
+        )*
+    };
+}
+
+impl_into_colour!(Hex, Rgb, Rgba, Hsl, Hsla, LinearGradient, RadialGradient, ConicGradient);
+
+fn validate_alpha(alpha: f32) -> Result<(), ColourError> {
+    // also rejects NaN
+    if !(0.0..=1.0).contains(&alpha) {
+        return Err(ColourError::OutOfRange("alpha"));
+    }
+
+    Ok(())
+}
+
+fn validate_stops(stops: &[ColourStop]) -> Result<(), ColourError> {
+    if stops.len() < 2 {
+        return Err(ColourError::NotEnoughStops);
+    }
+
+    for stop in stops {
+        if stop.colour.is_gradient() {
+            return Err(ColourError::NestedGradient);
+        }
+
+        if stop.position.is_some_and(|position| position > 100) {
+            return Err(ColourError::OutOfRange("stop position"));
+        }
+
+        stop.colour.validate()?;
+    }
+
+    Ok(())
+}
+
+/// Parses a trimmed, lowercase colour. Values are range checked by `Colour::validate`
+fn parse_colour(s: &str) -> Option<Colour> {
+    if let Some(hex) = s.strip_prefix('#') {
+        return Hex::parse(hex).map(Colour::Hex);
+    }
+
+    if let Some((name, args)) = split_function(s) {
+        let colour = match name {
+            // `rgb` and `rgba` (like `hsl` and `hsla`) are aliases, the alpha decides the kind
+            "rgb" | "rgba" => {
+                let ([r, g, b], alpha) = split_components(args)?;
+                let (r, g, b) = (parse_channel(r)?, parse_channel(g)?, parse_channel(b)?);
+
+                match alpha {
+                    Some(alpha) => Colour::Rgba(Rgba::new(r, g, b, parse_alpha(alpha)?)),
+                    None => Colour::Rgb(Rgb::new(r, g, b)),
+                }
+            },
+            "hsl" | "hsla" => {
+                let ([h, s, l], alpha) = split_components(args)?;
+
+                let h = h.strip_suffix("deg").unwrap_or(h).parse().ok()?;
+                let s = s.strip_suffix('%')?.parse().ok()?;
+                let l = l.strip_suffix('%')?.parse().ok()?;
+
+                match alpha {
+                    Some(alpha) => Colour::Hsla(Hsla::new(h, s, l, parse_alpha(alpha)?)),
+                    None => Colour::Hsl(Hsl::new(h, s, l)),
+                }
+            },
+            "var" => Colour::Variable(args.trim().strip_prefix("--")?.to_string()),
+            _ => return parse_gradient(name, args),
+        };
+
+        return Some(colour);
+    }
+
+    if !s.is_empty() && s.chars().all(|c| c.is_ascii_lowercase()) {
+        return Some(Colour::Named(s.to_string()));
+    }
+
+    None
+}
+
+fn parse_gradient(name: &str, args: &str) -> Option<Colour> {
+    let (is_repeating, name) = match name.strip_prefix("repeating-") {
+        Some(name) => (true, name),
+        None => (false, name),
+    };
+
+    let mut args = split_top_level(args).into_iter().map(str::trim).peekable();
+
+    let colour = match name {
+        "linear-gradient" => {
+            let angle = args.peek().and_then(|arg| parse_angle(arg));
+
+            if angle.is_some() {
+                args.next();
+            }
+
+            Colour::LinearGradient(LinearGradient {
+                angle,
+                stops: args.map(ColourStop::parse).collect::<Option<_>>()?,
+                is_repeating,
+            })
+        },
+        "radial-gradient" => {
+            let mut shape = ColorShape::Ellipse;
+            let mut shape_position = 100;
+
+            if let Some(arg) = args.peek().copied() {
+                let (shape_name, position) = match arg.split_once(" at ") {
+                    Some((shape_name, position)) => (shape_name.trim(), Some(position.trim())),
+                    None => (arg, None),
+                };
+
+                let parsed_shape = match shape_name {
+                    "circle" => Some(ColorShape::Circle),
+                    "ellipse" => Some(ColorShape::Ellipse),
+                    _ => None,
+                };
+
+                if let Some(parsed_shape) = parsed_shape {
+                    shape = parsed_shape;
+
+                    if let Some(position) = position {
+                        shape_position = position.strip_suffix('%')?.parse().ok()?;
+                    }
+
+                    args.next();
+                }
+            }
+
+            Colour::RadialGradient(RadialGradient {
+                shape,
+                shape_position,
+                stops: args.map(ColourStop::parse).collect::<Option<_>>()?,
+                is_repeating,
+            })
+        },
+        "conic-gradient" => {
+            let angle = args.peek()
+                .and_then(|arg| arg.strip_prefix("from "))
+                .and_then(|angle| parse_angle(angle.trim()));
+
+            if angle.is_some() {
+                args.next();
+            }
+
+            Colour::ConicGradient(ConicGradient {
+                angle,
+                stops: args.map(ColourStop::parse).collect::<Option<_>>()?,
+                is_repeating,
+            })
+        },
+        _ => return None,
+    };
+
+    Some(colour)
+}
+
+/// Parses an angle in degrees, or a direction like `to top right`
+fn parse_angle(s: &str) -> Option<u16> {
+    if let Some(degrees) = s.strip_suffix("deg") {
+        return degrees.trim().parse::<u16>().ok().map(|degrees| degrees % 360);
+    }
+
+    let angle = match s.strip_prefix("to ")?.split_whitespace().collect::<Vec<_>>().as_slice() {
+        ["top"] => 0,
+        ["top", "right"] | ["right", "top"] => 45,
+        ["right"] => 90,
+        ["bottom", "right"] | ["right", "bottom"] => 135,
+        ["bottom"] => 180,
+        ["bottom", "left"] | ["left", "bottom"] => 225,
+        ["left"] => 270,
+        ["top", "left"] | ["left", "top"] => 315,
+        _ => return None,
+    };
+
+    Some(angle)
+}
+
+/// Splits `name(args)` into its name and arguments
+fn split_function(s: &str) -> Option<(&str, &str)> {
+    let (name, rest) = s.split_once('(')?;
+
+    Some((name.trim(), rest.strip_suffix(')')?))
+}
+
+/// Splits the arguments of `rgb()` or `hsl()` into three components and an optional alpha,
+/// written either as `255, 0, 0, 0.5` or as `255 0 0 / 50%`
+fn split_components(args: &str) -> Option<([&str; 3], Option<&str>)> {
+    let (components, alpha) = match args.split_once('/') {
+        Some((components, alpha)) => (components, Some(alpha.trim())),
+        None => (args, None),
+    };
+
+    let mut components = components.split(|c: char| c == ',' || c.is_whitespace())
+        .filter(|component| !component.is_empty())
+        .collect::<Vec<_>>();
+
+    let alpha = match alpha {
+        Some(alpha) => Some(alpha),
+        None if components.len() == 4 => components.pop(),
+        None => None,
+    };
+
+    Some((components.try_into().ok()?, alpha))
+}
+
+/// Parses a colour channel from `0` to `255`, or a percentage of it
+fn parse_channel(s: &str) -> Option<u8> {
+    match s.strip_suffix('%') {
+        Some(percent) => {
+            let percent = percent.parse::<f32>().ok()?;
+
+            (0.0..=100.0).contains(&percent).then(|| (percent * 2.55).round() as u8)
+        },
+        None => s.parse().ok(),
+    }
+}
+
+/// Parses an alpha from `0` to `1`, or a percentage. The range is checked by `validate_alpha`
+fn parse_alpha(s: &str) -> Option<f32> {
+    match s.strip_suffix('%') {
+        Some(percent) => percent.parse::<f32>().ok().map(|percent| percent / 100.0),
+        None => s.parse().ok(),
+    }
+}
+
+/// Splits on the commas that aren't inside parentheses
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0usize;
+    let mut start = 0;
+
+    for (i, c) in s.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            },
+            _ => (),
+        }
+    }
+
+    parts.push(&s[start..]);
+
+    parts
+}
+
+/// A colour written as `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hex {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: Option<u8>,
+}
+
+impl Display for Hex {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)?;
+
+        if let Some(a) = self.a {
+            write!(f, "{a:02x}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Hex {
+    pub fn new(r: u8, g: u8, b: u8) -> Self {
+        Self { r, g, b, a: None }
+    }
+
+    pub fn new_with_alpha(r: u8, g: u8, b: u8, a: u8) -> Self {
+        Self { r, g, b, a: Some(a) }
+    }
+
+    /// Parses the hex digits of a colour, without the leading `#`
+    fn parse(digits: &str) -> Option<Self> {
+        if !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return None;
+        }
+
+        let channels = match digits.len() {
+            // shorthand digits are doubled, e.g. `f` is `ff`
+            3 | 4 => digits.chars()
+                .map(|c| u8::from_str_radix(&c.to_string().repeat(2), 16).ok())
+                .collect::<Option<Vec<_>>>()?,
+            6 | 8 => (0..digits.len()).step_by(2)
+                .map(|i| u8::from_str_radix(&digits[i..i + 2], 16).ok())
+                .collect::<Option<Vec<_>>>()?,
+            _ => return None,
+        };
+
+        Some(Self {
+            r: channels[0],
+            g: channels[1],
+            b: channels[2],
+            a: channels.get(3).copied(),
+        })
+    }
+}
+
+/// A colour in a gradient, optionally placed at a percentage of its length
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColourStop {
+    pub colour: Colour,
+    pub position: Option<u8>,
+}
+
+impl Display for ColourStop {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.colour)?;
+
+        if let Some(position) = self.position {
+            write!(f, " {position}%")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl ColourStop {
+    pub fn new<C: Into<Colour>>(colour: C) -> Self {
+        Self {
+            colour: colour.into(),
+            position: None,
+        }
+    }
+
+    /// Places the colour at `position` percent of the gradient
+    pub fn at(mut self, position: u8) -> Self {
+        self.position = Some(position);
+
+        self
+    }
+
+    fn parse(s: &str) -> Option<Self> {
+        // the position is the last word, unless it's part of the colour itself
+        if let Some((colour, position)) = s.rsplit_once(' ') {
+            let position = match position {
+                "0" => Some(0),
+                _ => position.strip_suffix('%').and_then(|position| position.parse().ok()),
+            };
+
+            if let Some(position) = position {
+                return Some(Self {
+                    colour: parse_colour(colour.trim())?,
+                    position: Some(position),
+                });
+            }
+        }
+
+        Some(Self::new(parse_colour(s)?))
+    }
+}
+
+fn write_stops(f: &mut std::fmt::Formatter<'_>, stops: &[ColourStop]) -> std::fmt::Result {
+    let stops = stops.iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+
+    write!(f, "{}", stops.join(", "))
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct LinearGradient {
-    pub color_a: String,
-    pub color_a_percent: u8,
-    
-    pub color_b: String,
-    pub color_b_percent: u8,
+    /// The direction of the gradient in degrees, `0` pointing up
+    pub angle: Option<u16>,
+    pub stops: Vec<ColourStop>,
 
     pub is_repeating: bool,
 }
 
-impl ToString for LinearGradient {
-    fn to_string(&self) -> String {
-        let mut col_a = self.color_a.clone();
+impl Display for LinearGradient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}linear-gradient(", if self.is_repeating { "repeating-" } else { "" })?;
 
-        if self.color_a_percent < 100 {
-            col_a.push_str(&format!(" {}%", self.color_a_percent))
+        if let Some(angle) = self.angle {
+            write!(f, "{angle}deg, ")?;
         }
 
-        let mut col_b = self.color_b.clone();
-        if self.color_b_percent < 100 {
-            col_b.push_str(&format!(" {}%", self.color_b_percent))
-        }
-        
-        format!("{}linear-gradient({col_a}, {col_b})", if self.is_repeating { "repeating-" } else { "" })
+        write_stops(f, &self.stops)?;
+        write!(f, ")")
     }
 }
 
 impl LinearGradient {
-    pub fn new(color_a: String, color_a_percent: u8, color_b: String, color_b_percent: u8) -> Self {
+    pub fn new(stops: Vec<ColourStop>) -> Self {
         Self {
-            color_a,
-            color_a_percent,
-            color_b,
-            color_b_percent,
+            angle: None,
+            stops,
             is_repeating: false
         }
     }
 
-    pub fn new_repeating(color_a: String, color_a_percent: u8, color_b: String, color_b_percent: u8) -> Self {
+    pub fn new_repeating(stops: Vec<ColourStop>) -> Self {
         Self {
-            color_a,
-            color_a_percent,
-            color_b,
-            color_b_percent,
+            angle: None,
+            stops,
             is_repeating: true
         }
     }
+
+    pub fn with_angle(mut self, angle: u16) -> Self {
+        self.angle = Some(angle % 360);
+
+        self
+    }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Rgb {
     pub r: u8,
     pub g: u8,
     pub b: u8,
 }
 
-impl ToString for Rgb {
-    fn to_string(&self) -> String {
-        format!("rgb({}, {}, {})", self.r, self.g, self.b)
+impl Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rgb({}, {}, {})", self.r, self.g, self.b)
     }
 }
 
@@ -67,25 +614,96 @@ impl Rgb {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Rgba {
     pub r: u8,
     pub g: u8,
     pub b: u8,
-    pub a: u8,
+    /// The opacity, from `0.0` to `1.0`
+    pub a: f32,
 }
 
-impl ToString for Rgba {
-    fn to_string(&self) -> String {
-        format!("rgba({}, {}, {}, {})", self.r, self.g, self.b, self.a)
+impl Display for Rgba {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rgba({}, {}, {}, {})", self.r, self.g, self.b, self.a)
     }
 }
 
 impl Rgba {
-    pub fn new(r: u8, g: u8, b: u8, a: u8) -> Self {
+    pub fn new(r: u8, g: u8, b: u8, a: f32) -> Self {
         Self { r, g, b, a }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hsl {
+    /// The hue in degrees, below 360
+    pub h: u16,
+    /// The saturation in percent
+    pub s: u8,
+    /// The lightness in percent
+    pub l: u8,
+}
+
+impl Display for Hsl {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "hsl({}, {}%, {}%)", self.h, self.s, self.l)
+    }
+}
+
+impl Hsl {
+    pub fn new(h: u16, s: u8, l: u8) -> Self {
+        Self { h, s, l }
+    }
+
+    fn validate(&self) -> Result<(), ColourError> {
+        if self.h >= 360 {
+            return Err(ColourError::OutOfRange("hue"));
+        }
+
+        if self.s > 100 {
+            return Err(ColourError::OutOfRange("saturation"));
+        }
+
+        if self.l > 100 {
+            return Err(ColourError::OutOfRange("lightness"));
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsla {
+    /// The hue in degrees, below 360
+    pub h: u16,
+    /// The saturation in percent
+    pub s: u8,
+    /// The lightness in percent
+    pub l: u8,
+    /// The opacity, from `0.0` to `1.0`
+    pub a: f32,
+}
+
+impl Display for Hsla {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "hsla({}, {}%, {}%, {})", self.h, self.s, self.l, self.a)
+    }
+}
+
+impl Hsla {
+    pub fn new(h: u16, s: u8, l: u8, a: f32) -> Self {
+        Self { h, s, l, a }
+    }
+
+    fn validate(&self) -> Result<(), ColourError> {
+        Hsl::new(self.h, self.s, self.l).validate()?;
+
+        validate_alpha(self.a)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ColorShape {
     Circle,
     Ellipse
@@ -100,20 +718,20 @@ impl Display for ColorShape {
     }
 }
 
+#[derive(Debug, Clone, PartialEq)]
 pub struct RadialGradient {
     pub shape: ColorShape,
+    /// Where the shape is centered, in percent. `100` leaves it out
     pub shape_position: u8,
-    
-    pub color_a: String,
-    pub color_b: String,
+    pub stops: Vec<ColourStop>,
 
     pub is_repeating: bool,
 }
 
-impl ToString for RadialGradient {
-    fn to_string(&self) -> String {
+impl Display for RadialGradient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut shape = self.shape.to_string().to_lowercase();
-        
+
         if self.shape_position < 100 {
             shape.push_str(&format!(" at {}%", self.shape_position));
         }
@@ -124,28 +742,204 @@ impl ToString for RadialGradient {
             ""
         };
 
-        format!("{}radial-gradient({}, {}, {})", repeat, shape, self.color_a, self.color_b)
+        write!(f, "{}radial-gradient({}, ", repeat, shape)?;
+        write_stops(f, &self.stops)?;
+        write!(f, ")")
     }
 }
 
 impl RadialGradient {
-    pub fn new(shape: ColorShape, shape_position: u8, color_a: String, color_b: String) -> Self {
+    pub fn new(shape: ColorShape, shape_position: u8, stops: Vec<ColourStop>) -> Self {
         Self {
             shape,
             shape_position,
-            color_a,
-            color_b,
+            stops,
             is_repeating: false
         }
     }
 
-    pub fn new_repeating(shape: ColorShape, shape_position: u8, color_a: String, color_b: String) -> Self {
+    pub fn new_repeating(shape: ColorShape, shape_position: u8, stops: Vec<ColourStop>) -> Self {
         Self {
             shape,
             shape_position,
-            color_a,
-            color_b,
+            stops,
             is_repeating: true
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConicGradient {
+    /// The angle the gradient starts at in degrees, `0` pointing up
+    pub angle: Option<u16>,
+    pub stops: Vec<ColourStop>,
+
+    pub is_repeating: bool,
+}
+
+impl Display for ConicGradient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}conic-gradient(", if self.is_repeating { "repeating-" } else { "" })?;
+
+        if let Some(angle) = self.angle {
+            write!(f, "from {angle}deg, ")?;
+        }
+
+        write_stops(f, &self.stops)?;
+        write!(f, ")")
+    }
+}
+
+impl ConicGradient {
+    pub fn new(stops: Vec<ColourStop>) -> Self {
+        Self {
+            angle: None,
+            stops,
+            is_repeating: false
+        }
+    }
+
+    pub fn new_repeating(stops: Vec<ColourStop>) -> Self {
+        Self {
+            angle: None,
+            stops,
+            is_repeating: true
+        }
+    }
+
+    pub fn with_angle(mut self, angle: u16) -> Self {
+        self.angle = Some(angle % 360);
+
+        self
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> Colour {
+        s.parse().unwrap_or_else(|err| panic!("could not parse `{s}`: {err}"))
+    }
+
+    #[test]
+    fn parses_hex() {
+        assert_eq!(parse("#ff0000"), Colour::Hex(Hex::new(255, 0, 0)));
+        assert_eq!(parse("#F00"), Colour::Hex(Hex::new(255, 0, 0)));
+        assert_eq!(parse("#ff000080"), Colour::Hex(Hex::new_with_alpha(255, 0, 0, 128)));
+        assert_eq!(parse("#f008"), Colour::Hex(Hex::new_with_alpha(255, 0, 0, 136)));
+        assert!("#ff00f".parse::<Colour>().is_err());
+        assert!("#gg0000".parse::<Colour>().is_err());
+    }
+
+    #[test]
+    fn parses_rgb() {
+        assert_eq!(parse("rgb(255, 0, 0)"), Colour::Rgb(Rgb::new(255, 0, 0)));
+        assert_eq!(parse("rgb(255,0,0)"), Colour::Rgb(Rgb::new(255, 0, 0)));
+        assert_eq!(parse("rgb(255 0 0)"), Colour::Rgb(Rgb::new(255, 0, 0)));
+        assert_eq!(parse("rgb(100%, 50%, 0%)"), Colour::Rgb(Rgb::new(255, 128, 0)));
+        assert!("rgb(256, 0, 0)".parse::<Colour>().is_err());
+        assert!("rgb(101%, 0%, 0%)".parse::<Colour>().is_err());
+        assert!("rgb(255, 0)".parse::<Colour>().is_err());
+    }
+
+    #[test]
+    fn parses_rgba() {
+        assert_eq!(parse("rgba(255, 0, 0, 0.5)"), Colour::Rgba(Rgba::new(255, 0, 0, 0.5)));
+        assert_eq!(parse("rgba(255, 0, 0, 50%)"), Colour::Rgba(Rgba::new(255, 0, 0, 0.5)));
+        assert_eq!(parse("rgb(255 0 0 / 50%)"), Colour::Rgba(Rgba::new(255, 0, 0, 0.5)));
+        assert_eq!(parse("rgba(255 0 0 / 0.25)"), Colour::Rgba(Rgba::new(255, 0, 0, 0.25)));
+        assert_eq!(parse("rgba(255, 0, 0)"), Colour::Rgb(Rgb::new(255, 0, 0)));
+        assert_eq!(parse("rgba(255, 0, 0, 0.5)").to_string(), "rgba(255, 0, 0, 0.5)");
+
+        assert_eq!("rgba(255, 0, 0, 2)".parse::<Colour>(), Err(ColourError::OutOfRange("alpha")));
+        assert_eq!("rgba(255, 0, 0, 150%)".parse::<Colour>(), Err(ColourError::OutOfRange("alpha")));
+        assert!("rgba(255, 0, 0, nan)".parse::<Colour>().is_err());
+    }
+
+    #[test]
+    fn parses_hsl() {
+        assert_eq!(parse("hsl(120, 50%, 25%)"), Colour::Hsl(Hsl::new(120, 50, 25)));
+        assert_eq!(parse("hsl(120deg 50% 25%)"), Colour::Hsl(Hsl::new(120, 50, 25)));
+        assert_eq!("hsl(360, 50%, 25%)".parse::<Colour>(), Err(ColourError::OutOfRange("hue")));
+        assert_eq!("hsl(120, 101%, 25%)".parse::<Colour>(), Err(ColourError::OutOfRange("saturation")));
+        assert!("hsl(120, 50, 25)".parse::<Colour>().is_err());
+    }
+
+    #[test]
+    fn parses_hsla() {
+        assert_eq!(parse("hsla(120, 50%, 25%, 0.5)"), Colour::Hsla(Hsla::new(120, 50, 25, 0.5)));
+        assert_eq!(parse("hsl(120 50% 25% / 40%)"), Colour::Hsla(Hsla::new(120, 50, 25, 0.4)));
+        assert_eq!(parse("hsla(120, 50%, 25%, 0.5)").to_string(), "hsla(120, 50%, 25%, 0.5)");
+        assert_eq!("hsla(120, 50%, 25%, -1)".parse::<Colour>(), Err(ColourError::OutOfRange("alpha")));
+    }
+
+    #[test]
+    fn parses_named_colours_and_variables() {
+        assert_eq!(parse("RebeccaPurple"), Colour::Named("rebeccapurple".to_string()));
+        assert_eq!(parse("var(--accent)"), Colour::Variable("accent".to_string()));
+        assert_eq!(parse("var(--accent)").to_string(), "var(--accent)");
+        assert!("notacolour".parse::<Colour>().is_err());
+        assert!("var(accent)".parse::<Colour>().is_err());
+        assert!("var(--Accent_1)".parse::<Colour>().is_err());
+    }
+
+    #[test]
+    fn parses_linear_gradients() {
+        let expected = LinearGradient::new(vec![
+            ColourStop::new(Hex::new(255, 0, 0)),
+            ColourStop::new(Colour::Named("blue".to_string())).at(100),
+        ]).with_angle(90);
+
+        assert_eq!(parse("linear-gradient(90deg, #ff0000, blue 100%)"), Colour::LinearGradient(expected.clone()));
+        assert_eq!(parse("linear-gradient(to right, #ff0000, blue 100%)"), Colour::LinearGradient(expected));
+
+        let repeating = parse("repeating-linear-gradient(red, rgba(0, 0, 255, 0.5) 50%)");
+        assert_eq!(repeating.to_string(), "repeating-linear-gradient(red, rgba(0, 0, 255, 0.5) 50%)");
+
+        assert_eq!("linear-gradient(red)".parse::<Colour>(), Err(ColourError::NotEnoughStops));
+        assert_eq!(
+            "linear-gradient(red, linear-gradient(red, blue))".parse::<Colour>(),
+            Err(ColourError::NestedGradient),
+        );
+    }
+
+    #[test]
+    fn parses_radial_and_conic_gradients() {
+        let radial = parse("radial-gradient(circle at 50%, red, blue)");
+        assert_eq!(radial, Colour::RadialGradient(RadialGradient::new(ColorShape::Circle, 50, vec![
+            ColourStop::new(Colour::Named("red".to_string())),
+            ColourStop::new(Colour::Named("blue".to_string())),
+        ])));
+        assert_eq!(radial.to_string(), "radial-gradient(circle at 50%, red, blue)");
+
+        let conic = parse("conic-gradient(from 45deg, red, var(--accent) 50%)");
+        assert_eq!(conic.to_string(), "conic-gradient(from 45deg, red, var(--accent) 50%)");
+    }
+
+    #[test]
+    fn rejects_long_colours() {
+        let long = format!("linear-gradient({})", vec!["red"; 40].join(", "));
+
+        assert_eq!(long.parse::<Colour>(), Err(ColourError::TooLong { max: MAX_COLOUR_LENGTH }));
+    }
+
+    #[test]
+    fn keeps_received_colours_as_written() {
+        for raw in ["#fff", "rgb(255,0,0)", "#FF0000", "not a colour"] {
+            let colour = serde_json::from_value::<Colour>(serde_json::json!(raw)).unwrap();
+
+            assert_eq!(colour, Colour::Raw(raw.to_string()));
+            assert_eq!(serde_json::to_value(&colour).unwrap(), serde_json::json!(raw));
+        }
+
+        let colour = serde_json::from_value::<Colour>(serde_json::json!("#ffffff")).unwrap();
+        assert_eq!(colour, Colour::Hex(Hex::new(255, 255, 255)));
+
+        let colour = Colour::Raw("#fff".to_string());
+        assert_eq!(colour.normalize(), Ok(Colour::Hex(Hex::new(255, 255, 255))));
+        assert!(colour.validate().is_ok());
+        assert!(Colour::Raw("not a colour".to_string()).validate().is_err());
+    }
+}
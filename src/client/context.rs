@@ -1,107 +1,169 @@
-use crate::{http::prelude::HttpClient, cache::Cache, models::{user::User, server::Server, channel::Channel, message::Message}, };
+use std::sync::Arc;
 
-use super::{harmony_error::HarmonyError, RevoltClient};
+use tokio::sync::RwLock;
+
+use crate::{http::prelude::HttpClient, cache::{Cache, CacheHttp}, models::{user::User, server::Server, channel::Channel, message::Message, member::Member, id::{ChannelId, MessageId, ServerId, UserId}}, };
+
+use super::{harmony_error::HarmonyError, RevoltClient, data::TypeMap, typing::Typing};
 
 
 pub struct Context {
     pub client: RevoltClient,
-    pub user: User,
+    /// The client's own user, `None` if it couldn't be fetched, e.g. once the session is invalidated
+    pub user: Option<User>,
     pub server: Option<Server>,
     pub channel: Option<Channel>,
     pub message: Option<Message>,
 
     pub cache: Cache,
     pub http: HttpClient,
+    /// State shared between every handler, keyed by type
+    pub data: Arc<RwLock<TypeMap>>,
 
     pub(crate) updated_cache: Cache,
+    /// Set when the session was invalidated while handling the event,
+    /// along with the token returned by `EventHandler::session_invalidated`
+    pub(crate) session_invalidated: Option<Option<String>>,
 }
 
 impl Context {
-    pub async fn user(&mut self, user_id: &str) -> Result<User, HarmonyError> {
+    /// Checks if `user_id` is the client's own user
+    pub fn is_current_user(&self, user_id: &UserId) -> bool {
+        self.user.as_ref().is_some_and(|user| &user.id == user_id)
+    }
+
+    /// Shows the typing indicator in a channel until the returned guard is dropped
+    pub fn typing(&self, channel_id: &ChannelId) -> Typing {
+        Typing::start(self.client.socket.client_sender.clone(), channel_id)
+    }
+
+    pub async fn user(&mut self, user_id: &UserId) -> Result<User, HarmonyError> {
         if let Some(user) = self.cache.get_user(user_id) {
             Ok(user)
         } else {
-            let http_res = self.http.get_user(&user_id).await;
+            let http_res = self.http.get_user(user_id).await;
 
             match http_res {
                 Ok(user) => {
-                    self.updated_cache.users.insert(user_id.to_string(), user.clone());
-                    self.cache.users.insert(user_id.to_string(), user.clone());
+                    self.updated_cache.users.insert(user_id.clone(), user.clone());
+                    self.cache.users.insert(user_id.clone(), user.clone());
                     
                     Ok(user)
                 },
                 Err(http_err) => {
-                    Err(HarmonyError::HttpError(http_err))
+                    Err(http_err.into())
                 }
             }
         }
     }
 
-    pub async fn message(&mut self, channel_id: &str, message_id: &str) -> Result<Message, HarmonyError> {
+    pub async fn message(&mut self, channel_id: &ChannelId, message_id: &MessageId) -> Result<Message, HarmonyError> {
         if let Some(message) = self.cache.get_message(message_id) {
             Ok(message)
         } else {
-            let http_res = self.http.get_message(&channel_id, &message_id).await;
+            let http_res = self.http.get_message(channel_id, message_id).await;
 
             match http_res {
                 Ok(message) => {
                     if self.cache.messages.len() >= self.cache.max_messages {
                         // is this even the right way to do this
                         let _ = self.cache.messages.pop_first();
-                        self.cache.messages.insert(message_id.to_string(), message.clone());
+                        self.cache.messages.insert(message_id.clone(), message.clone());
                     }
 
                     if self.updated_cache.messages.len() >= self.cache.max_messages {
                         let _ = self.updated_cache.messages.pop_first();
-                        self.updated_cache.messages.insert(message_id.to_string(), message.clone());
+                        self.updated_cache.messages.insert(message_id.clone(), message.clone());
                     }
                     
                     Ok(message)
                 },
                 Err(http_err) => {
-                    Err(HarmonyError::HttpError(http_err))
+                    Err(http_err.into())
                 }
             }
         }
     }
 
-    pub async fn channel(&mut self, channel_id: &str) -> Result<Channel, HarmonyError> {
+    /// Gets the previous versions of a cached message, oldest first
+    pub fn message_history(&self, message_id: &MessageId) -> Vec<Message> {
+        self.cache.get_message_history(message_id)
+    }
+
+    pub async fn channel(&mut self, channel_id: &ChannelId) -> Result<Channel, HarmonyError> {
         if let Some(channel) = self.cache.get_channel(channel_id) {
             Ok(channel)
         } else {
-            let http_res = self.http.get_channel(&channel_id).await;
+            let http_res = self.http.get_channel(channel_id).await;
 
             match http_res {
                 Ok(channel) => {
-                    self.updated_cache.channels.insert(channel_id.to_string(), channel.clone());
-                    self.cache.channels.insert(channel_id.to_string(), channel.clone());
+                    self.updated_cache.channels.insert(channel_id.clone(), channel.clone());
+                    self.cache.channels.insert(channel_id.clone(), channel.clone());
                     
                     Ok(channel)
                 },
                 Err(http_err) => {
-                    Err(HarmonyError::HttpError(http_err))
+                    Err(http_err.into())
                 }
             }
         }
     }
 
-    pub async fn server(&mut self, server_id: &str) -> Result<Server, HarmonyError> {
+    pub async fn member(&mut self, server_id: &ServerId, user_id: &UserId) -> Result<Member, HarmonyError> {
+        if let Some(member) = self.cache.get_member(server_id, user_id) {
+            Ok(member)
+        } else {
+            let member = self.http.get_member(server_id, user_id).await?;
+
+            self.updated_cache.add_member(member.clone());
+            self.cache.add_member(member.clone());
+
+            Ok(member)
+        }
+    }
+
+    /// Resolves the name shown for a user in a server: their nickname,
+    /// then their display name and finally their username
+    pub async fn display_name(&mut self, server_id: &ServerId, user_id: &UserId) -> Result<String, HarmonyError> {
+        let user = self.user(user_id).await?;
+
+        match self.member(server_id, user_id).await {
+            Ok(member) => Ok(member.display_name(&user)),
+            Err(err) if err.is_not_found() => Ok(user.get_display_name()),
+            Err(err) => Err(err),
+        }
+    }
+
+    pub async fn server(&mut self, server_id: &ServerId) -> Result<Server, HarmonyError> {
         if let Some(server) = self.cache.get_server(server_id) {
             Ok(server)
         } else {
-            let http_res = self.http.get_server(&server_id).await;
+            let http_res = self.http.get_server(server_id).await;
 
             match http_res {
                 Ok(server) => {
-                    self.updated_cache.servers.insert(server_id.to_string(), server.clone());
-                    self.cache.servers.insert(server_id.to_string(), server.clone());
+                    self.updated_cache.servers.insert(server_id.clone(), server.clone());
+                    self.cache.servers.insert(server_id.clone(), server.clone());
                     
                     Ok(server)
                 },
                 Err(http_err) => {
-                    Err(HarmonyError::HttpError(http_err))
+                    Err(http_err.into())
                 }
             }
         }
     }
-}
\ No newline at end of file
+}
+
+impl AsRef<HttpClient> for Context {
+    fn as_ref(&self) -> &HttpClient {
+        &self.http
+    }
+}
+
+impl CacheHttp for Context {
+    fn shared_cache(&self) -> Option<&Arc<RwLock<Cache>>> {
+        Some(&self.client.cache)
+    }
+}
@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 
-use crate::{websocket::SocketError, models::{events::server::*, message::{Message, PartialMessage}, channel::Channel, server::Server, Emoji}};
+use crate::{websocket::SocketError, models::{events::server::*, message::Message, user::User, channel::Channel, server::Server, Emoji, id::{ChannelId, EmojiId, ServerId}}};
 
 use super::context::Context;
 
@@ -22,11 +22,16 @@ pub trait EventHandler: Send + Sync {
 
     async fn message_received(&self, _ctx: &mut Context, _msg: Message) { }
 
-    async fn message_updated(&self, _ctx: &mut Context, _msg: PartialMessage) { }
+    /// A message was edited. `old` is the cached message before the edit and `new` is it with
+    /// the edit applied; both are `None` when the message wasn't cached
+    async fn message_updated(&self, _ctx: &mut Context, _update: MessageUpdateEvent, _old: Option<Message>, _new: Option<Message>) { }
 
-    async fn embed_append(&self, _ctx: &mut Context, _append: EmbedAppendEvent) { }
+    /// Embeds were added to a message. `old` and `new` are the cached message
+    /// before and after the embeds were added, if it was cached
+    async fn embed_append(&self, _ctx: &mut Context, _append: EmbedAppendEvent, _old: Option<Message>, _new: Option<Message>) { }
 
-    async fn message_deleted(&self, _ctx: &mut Context, _msg: Message) { }
+    /// A message was deleted. `msg` is the cached message, if it was cached
+    async fn message_deleted(&self, _ctx: &mut Context, _delete: MessageDeleteEvent, _msg: Option<Message>) { }
 
     async fn message_reacted(&self, _ctx: &mut Context, _msg_react: MessageReactEvent) { }
 
@@ -38,7 +43,7 @@ pub trait EventHandler: Send + Sync {
 
     async fn channel_updated(&self, _ctx: &mut Context, _channel_event: ChannelUpdateEvent) { }
 
-    async fn channel_deleted(&self, _ctx: &mut Context, _channel_id: String) { }
+    async fn channel_deleted(&self, _ctx: &mut Context, _channel_id: ChannelId) { }
 
     async fn user_joined_group(&self, _ctx: &mut Context, _channel_event: Event) { }
 
@@ -54,19 +59,33 @@ pub trait EventHandler: Send + Sync {
 
     async fn server_updated(&self, _ctx: &mut Context, _server_event: ServerUpdateEvent) { }
 
-    async fn server_deleted(&self, _ctx: &mut Context, _server_id: String) { }
+    async fn server_deleted(&self, _ctx: &mut Context, _server_id: ServerId) { }
 
-    async fn member_updated(&self, _ctx: &mut Context, _member_update: ServerMemberUpdate) { }
+    /// A member was updated. `old` and `new` are the cached member before
+    /// and after the update, both `None` when the member wasn't cached
+    async fn member_updated(&self, _ctx: &mut Context, _member_update: ServerMemberUpdate, _old: Option<Member>, _new: Option<Member>) { }
 
-    async fn member_joined(&self, _ctx: &mut Context, _member_event: Event) { }
+    async fn member_joined(&self, _ctx: &mut Context, _member_event: MemberJoinEvent) { }
 
-    async fn member_left(&self, _ctx: &mut Context, _member_event: Event) { }
+    async fn member_left(&self, _ctx: &mut Context, _member_event: MemberLeaveEvent) { }
+
+    /// The current user joined a server. The server and its channels have been added to the cache
+    async fn server_joined(&self, _ctx: &mut Context, _server: Server) { }
+
+    /// The current user left, was kicked or was banned from a server. The server, its channels and
+    /// their messages have been removed from the cache; `server` is the last cached version of it
+    async fn server_left(&self, _ctx: &mut Context, _server_id: ServerId, _server: Option<Server>, _reason: RemovalReason) { }
 
     async fn role_updated(&self, _ctx: &mut Context, _role_update: ServerRoleUpdate) { }
 
     async fn role_deleted(&self, _ctx: &mut Context, _role_event: RoleEvent) { }
 
-    async fn user_updated(&self, _ctx: &mut Context, _user_update: UserUpdate) { }
+    /// A user was updated. `old` and `new` are the cached user before
+    /// and after the update, both `None` when the user wasn't cached
+    async fn user_updated(&self, _ctx: &mut Context, _user_update: UserUpdate, _old: Option<User>, _new: Option<User>) { }
+
+    /// A user's online state or presence changed. Called after `user_updated`
+    async fn presence_updated(&self, _ctx: &mut Context, _presence_update: PresenceUpdate) { }
 
     async fn relationship_update(&self, _ctx: &mut Context, _relationship_update: UserRelationshipEvent) { }
 
@@ -74,7 +93,12 @@ pub trait EventHandler: Send + Sync {
 
     async fn emoji_created(&self, _ctx: &mut Context, _emoji: Emoji) { }
 
-    async fn emoji_deleted(&self, _ctx: &mut Context, _emoji_id: String) { }
+    async fn emoji_deleted(&self, _ctx: &mut Context, _emoji_id: EmojiId) { }
 
     async fn auth(&self, _ctx: &mut Context, _auth_event: AuthEvent) { }
+
+    /// The client's session was revoked, so its token can no longer be used.
+    ///
+    /// Return a new token to log in with it, or `None` to shut the client down (the default)
+    async fn session_invalidated(&self, _ctx: &mut Context) -> Option<String> { None }
 }
\ No newline at end of file
@@ -0,0 +1,73 @@
+use std::sync::Arc;
+
+use async_channel::Sender;
+use tokio::sync::{Notify, RwLock, RwLockReadGuard};
+
+use crate::{http::prelude::{HttpClient, HttpError}, cache::{Cache, CacheHttp}, metrics::Metrics, websocket::SocketError, models::{events::client::ClientEvent, message::{Message, PartialMessage}, id::ChannelId}};
+
+use super::{data::TypeMap, typing::Typing};
+
+/// A lightweight, cloneable handle to a running `RevoltClient`.
+///
+/// Created with `RevoltClient::handle`, it lets background tasks send messages,
+/// show typing indicators, read the cache and shut the client down.
+#[derive(Debug, Clone)]
+pub struct ClientHandle {
+    pub http: HttpClient,
+    pub(crate) socket_sender: Sender<ClientEvent>,
+    pub(crate) cache: Arc<RwLock<Cache>>,
+    pub data: Arc<RwLock<TypeMap>>,
+    pub metrics: Arc<Metrics>,
+    pub(crate) shutdown: Arc<Notify>,
+}
+
+impl ClientHandle {
+    /// Read access to the client's cache. The lock should not be held across long awaits,
+    /// as the client can't process events while it is held
+    pub async fn cache(&self) -> RwLockReadGuard<'_, Cache> {
+        self.cache.read().await
+    }
+
+    pub async fn say(&self, channel_id: &ChannelId, msg: &str) -> Result<Message, HttpError> {
+        self.http.say(channel_id, msg).await
+    }
+
+    pub async fn send_message(&self, channel_id: &ChannelId, msg: PartialMessage) -> Result<Message, HttpError> {
+        self.http.send_msg_in_channel(channel_id, msg).await
+    }
+
+    pub async fn start_typing(&self, channel_id: &ChannelId) -> Result<(), SocketError> {
+        self.send(ClientEvent::BeginTyping { channel_id: channel_id.clone() }).await
+    }
+
+    pub async fn stop_typing(&self, channel_id: &ChannelId) -> Result<(), SocketError> {
+        self.send(ClientEvent::EndTyping { channel_id: channel_id.clone() }).await
+    }
+
+    /// Shows the typing indicator in a channel until the returned guard is dropped
+    pub fn typing(&self, channel_id: &ChannelId) -> Typing {
+        Typing::start(self.socket_sender.clone(), channel_id)
+    }
+
+    /// Closes the websocket connection and makes `RevoltClient::listen` return
+    pub fn shutdown(&self) {
+        self.shutdown.notify_one();
+    }
+
+    async fn send(&self, event: ClientEvent) -> Result<(), SocketError> {
+        self.socket_sender.send(event).await
+            .map_err(|_| SocketError::SendError)
+    }
+}
+
+impl AsRef<HttpClient> for ClientHandle {
+    fn as_ref(&self) -> &HttpClient {
+        &self.http
+    }
+}
+
+impl CacheHttp for ClientHandle {
+    fn shared_cache(&self) -> Option<&Arc<RwLock<Cache>>> {
+        Some(&self.cache)
+    }
+}
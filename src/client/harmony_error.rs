@@ -1,7 +1,51 @@
-use crate::http::prelude::HttpError;
+use crate::{http::prelude::{HttpError, HttpClientBuilderError}, websocket::SocketError, models::{events::server::ServerError, message::MessageValidationError, channel::messageable::PurgeError}};
 
-#[derive(Debug)]
+use super::{RevoltClientError, builder::RevoltBuilderError};
+
+/// Any error that can occur within Harmony.
+///
+/// Every other error type converts into this one, so it can be used with `?`
+/// throughout an application. The original error is available through `source()`.
+#[derive(Debug, thiserror::Error)]
 pub enum HarmonyError {
-    HttpError(HttpError),
-    
-}
\ No newline at end of file
+    #[error(transparent)]
+    HttpError(#[from] HttpError),
+    #[error(transparent)]
+    HttpClientBuilderError(#[from] HttpClientBuilderError),
+    #[error(transparent)]
+    SocketError(#[from] SocketError),
+    #[error(transparent)]
+    ClientError(#[from] RevoltClientError),
+    #[error(transparent)]
+    BuilderError(#[from] RevoltBuilderError),
+    #[error("Revolt sent an error: {0}")]
+    ServerError(#[from] ServerError),
+    #[error(transparent)]
+    MessageValidationError(#[from] MessageValidationError),
+    #[error(transparent)]
+    PurgeError(#[from] PurgeError),
+}
+
+impl HarmonyError {
+    /// Checks if retrying the failed action could succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::HttpError(err) => err.is_retryable(),
+            Self::PurgeError(err) => err.source.is_retryable(),
+            Self::SocketError(err) => err.is_retryable(),
+            Self::ClientError(RevoltClientError::SocketError(err)) => err.is_retryable(),
+            Self::BuilderError(RevoltBuilderError::NodeQueryError(err)) => err.is_retryable(),
+            Self::BuilderError(RevoltBuilderError::SocketError(err)) => err.is_retryable(),
+            Self::ServerError(ServerError::InternalError) => true,
+            _ => false,
+        }
+    }
+
+    /// Checks if the error means a requested resource doesn't exist
+    pub fn is_not_found(&self) -> bool {
+        match self {
+            Self::HttpError(err) => err.is_not_found(),
+            _ => false,
+        }
+    }
+}
@@ -0,0 +1,344 @@
+use std::fmt::Display;
+
+use crate::models::id::{ChannelId, EmojiId, RoleId, UserId};
+
+/// Characters with a meaning in Revolt's markdown
+const MARKDOWN_CHARS: [char; 15] = ['\\', '*', '_', '~', '`', '|', '>', '#', '[', ']', '(', ')', '$', ':', '-'];
+
+/// Mentions that notify more than one user
+const MASS_MENTIONS: [&str; 2] = ["@everyone", "@online"];
+
+/// A zero width space, used to break up syntax without visibly changing the text
+const ZWSP: char = '\u{200B}';
+
+/// How a timestamp is shown to each user, in their own timezone
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TimestampStyle {
+    /// `16:20`
+    ShortTime,
+    /// `16:20:30`
+    LongTime,
+    /// `20/04/2021`
+    ShortDate,
+    /// `20 April 2021`
+    LongDate,
+    /// `20 April 2021 16:20`
+    #[default]
+    ShortDateTime,
+    /// `Tuesday, 20 April 2021 16:20`
+    LongDateTime,
+    /// `2 months ago`
+    Relative,
+}
+
+impl Display for TimestampStyle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let style = match self {
+            Self::ShortTime => "t",
+            Self::LongTime => "T",
+            Self::ShortDate => "d",
+            Self::LongDate => "D",
+            Self::ShortDateTime => "f",
+            Self::LongDateTime => "F",
+            Self::Relative => "R",
+        };
+
+        write!(f, "{style}")
+    }
+}
+
+/// Builds message content in Revolt's markdown dialect.
+///
+/// Text pushed with `push_safe` and the `*_safe` methods is escaped,
+/// everything else is pushed as is.
+///
+/// ```no_run
+/// # use harmony::{client::{context::Context, markdown::MarkdownBuilder}, models::message::Message};
+/// # async fn run(ctx: &Context, msg: &Message, user_input: &str) -> harmony::Result<()> {
+/// let content = MarkdownBuilder::new()
+///     .push_user_mention(&msg.author)
+///     .push(" said ")
+///     .push_quote_safe(user_input)
+///     .build();
+///
+/// ctx.http.say(&msg.channel, &content).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MarkdownBuilder {
+    content: String,
+}
+
+impl Display for MarkdownBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.content)
+    }
+}
+
+impl From<MarkdownBuilder> for String {
+    fn from(builder: MarkdownBuilder) -> Self {
+        builder.content
+    }
+}
+
+impl MarkdownBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push<D: Display>(mut self, text: D) -> Self {
+        self.content.push_str(&text.to_string());
+
+        self
+    }
+
+    /// Pushes text with its markdown and mentions escaped
+    pub fn push_safe(self, text: &str) -> Self {
+        self.push(escape(text))
+    }
+
+    pub fn push_line<D: Display>(self, text: D) -> Self {
+        self.push(text).newline()
+    }
+
+    pub fn newline(self) -> Self {
+        self.push('\n')
+    }
+
+    pub fn push_bold<D: Display>(self, text: D) -> Self {
+        self.push(format!("**{text}**"))
+    }
+
+    pub fn push_bold_safe(self, text: &str) -> Self {
+        self.push_bold(escape(text))
+    }
+
+    pub fn push_italic<D: Display>(self, text: D) -> Self {
+        self.push(format!("*{text}*"))
+    }
+
+    pub fn push_italic_safe(self, text: &str) -> Self {
+        self.push_italic(escape(text))
+    }
+
+    pub fn push_strikethrough<D: Display>(self, text: D) -> Self {
+        self.push(format!("~~{text}~~"))
+    }
+
+    pub fn push_strikethrough_safe(self, text: &str) -> Self {
+        self.push_strikethrough(escape(text))
+    }
+
+    /// Hides the text until it's clicked
+    pub fn push_spoiler<D: Display>(self, text: D) -> Self {
+        self.push(format!("||{text}||"))
+    }
+
+    pub fn push_spoiler_safe(self, text: &str) -> Self {
+        self.push_spoiler(escape(text))
+    }
+
+    /// Pushes inline code. Code isn't rendered as markdown, so it doesn't need escaping
+    pub fn push_code(self, code: &str) -> Self {
+        // the fence has to be longer than any run of backticks in the code to keep them
+        let longest_run = code.split(|c| c != '`')
+            .map(str::len)
+            .max()
+            .unwrap_or_default();
+
+        let fence = "`".repeat(longest_run + 1);
+
+        if code.starts_with('`') || code.ends_with('`') {
+            self.push(format!("{fence} {code} {fence}"))
+        } else {
+            self.push(format!("{fence}{code}{fence}"))
+        }
+    }
+
+    /// Pushes a code block, highlighted as `language` if it's set
+    pub fn push_codeblock(self, code: &str, language: Option<&str>) -> Self {
+        let language = language.unwrap_or_default();
+
+        self.push(format!("```{language}\n{}\n```\n", escape_codeblock(code)))
+    }
+
+    /// Pushes every line of `text` as a quote
+    pub fn push_quote<D: Display>(self, text: D) -> Self {
+        let quote = text.to_string()
+            .lines()
+            .map(|line| format!("> {line}\n"))
+            .collect::<String>();
+
+        self.push(quote)
+    }
+
+    pub fn push_quote_safe(self, text: &str) -> Self {
+        self.push_quote(escape(text))
+    }
+
+    /// Pushes a heading, `level` being between 1 and 6
+    pub fn push_heading<D: Display>(self, text: D, level: u8) -> Self {
+        let level = level.clamp(1, 6) as usize;
+
+        self.push(format!("{} {text}\n", "#".repeat(level)))
+    }
+
+    /// Pushes inline KaTeX
+    pub fn push_math(self, tex: &str) -> Self {
+        self.push(format!("${tex}$"))
+    }
+
+    /// Pushes KaTeX on its own line
+    pub fn push_math_block(self, tex: &str) -> Self {
+        self.push(format!("$$\n{tex}\n$$\n"))
+    }
+
+    /// Pushes a link showing `text` instead of the url
+    pub fn push_link<D: Display>(self, text: D, url: &str) -> Self {
+        self.push(format!("[{text}]({url})"))
+    }
+
+    /// Pushes a timestamp shown in each user's timezone, `unix_secs` being seconds since the epoch
+    pub fn push_timestamp(self, unix_secs: i64, style: TimestampStyle) -> Self {
+        self.push(format!("<t:{unix_secs}:{style}>"))
+    }
+
+    pub fn push_user_mention(self, user_id: &UserId) -> Self {
+        self.push(format!("<@{user_id}>"))
+    }
+
+    pub fn push_channel_mention(self, channel_id: &ChannelId) -> Self {
+        self.push(format!("<#{channel_id}>"))
+    }
+
+    pub fn push_role_mention(self, role_id: &RoleId) -> Self {
+        self.push(format!("<%{role_id}>"))
+    }
+
+    /// Pushes a custom emoji by its id
+    pub fn push_emoji(self, emoji_id: &EmojiId) -> Self {
+        self.push(format!(":{emoji_id}:"))
+    }
+
+    pub fn build(self) -> String {
+        self.content
+    }
+}
+
+/// Escapes markdown syntax and mentions, so `text` is shown exactly as written
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for c in text.chars() {
+        if MARKDOWN_CHARS.contains(&c) || c == '<' {
+            escaped.push('\\');
+        }
+
+        escaped.push(c);
+    }
+
+    escape_mass_mentions(&escaped)
+}
+
+/// Escapes only the mentions in `text`, leaving the rest of its markdown intact
+pub fn escape_mentions(text: &str) -> String {
+    let escaped = text.replace("<@", "\\<@")
+        .replace("<#", "\\<#")
+        .replace("<%", "\\<%");
+
+    escape_mass_mentions(&escaped)
+}
+
+/// Stops `@everyone` and `@online` from notifying anyone
+pub fn escape_mass_mentions(text: &str) -> String {
+    MASS_MENTIONS.iter().fold(text.to_string(), |text, mention| {
+        text.replace(mention, &mention.replacen('@', &format!("@{ZWSP}"), 1))
+    })
+}
+
+/// Stops `text` from closing the code block it's placed in
+pub fn escape_codeblock(text: &str) -> String {
+    text.replace("```", &format!("`{ZWSP}`{ZWSP}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn escapes_markdown_and_mentions() {
+        assert_eq!(escape("**bold** _it_ `code`"), "\\*\\*bold\\*\\* \\_it\\_ \\`code\\`");
+        assert_eq!(escape("<@01GXYZ0000000000000000000A>"), "\\<@01GXYZ0000000000000000000A\\>");
+        assert_eq!(escape("[link](https://revolt.chat)"), "\\[link\\]\\(https\\://revolt.chat\\)");
+        assert_eq!(escape("@everyone"), "@\u{200B}everyone");
+        assert_eq!(escape("plain text"), "plain text");
+    }
+
+    #[test]
+    fn escapes_only_mentions() {
+        assert_eq!(escape_mentions("**hi** <@01GXYZ0000000000000000000A>"), "**hi** \\<@01GXYZ0000000000000000000A>");
+        assert_eq!(escape_mentions("<#01GXYZ0000000000000000000A> <%01GXYZ0000000000000000000A>"), "\\<#01GXYZ0000000000000000000A> \\<%01GXYZ0000000000000000000A>");
+        assert_eq!(escape_mentions("@online"), "@\u{200B}online");
+    }
+
+    #[test]
+    fn escapes_mass_mentions() {
+        assert_eq!(escape_mass_mentions("hey @everyone and @online"), "hey @\u{200B}everyone and @\u{200B}online");
+        assert_eq!(escape_mass_mentions("@someone"), "@someone");
+    }
+
+    #[test]
+    fn escapes_codeblock_fences() {
+        assert_eq!(escape_codeblock("a ``` b"), "a `\u{200B}`\u{200B}` b");
+        assert_eq!(escape_codeblock("a `` b"), "a `` b");
+    }
+
+    #[test]
+    fn fences_inline_code_around_backticks() {
+        let code = |code| MarkdownBuilder::new().push_code(code).build();
+
+        assert_eq!(code("let x = 1;"), "`let x = 1;`");
+        assert_eq!(code("a ` b"), "``a ` b``");
+        assert_eq!(code("a `` b"), "```a `` b```");
+        assert_eq!(code("```"), "```` ``` ````");
+    }
+
+    #[test]
+    fn builds_markdown() {
+        let user_id = UserId::new("01GXYZ0000000000000000000A").unwrap();
+        let channel_id = ChannelId::new("01GXYZ0000000000000000000B").unwrap();
+        let role_id = RoleId::new("01GXYZ0000000000000000000C").unwrap();
+        let emoji_id = EmojiId::new("01GXYZ0000000000000000000D").unwrap();
+
+        let content = MarkdownBuilder::new()
+            .push_heading("Title", 9)
+            .push_bold_safe("*hi*")
+            .push(' ')
+            .push_italic("it")
+            .push(' ')
+            .push_strikethrough("no")
+            .push(' ')
+            .push_spoiler("secret")
+            .newline()
+            .push_user_mention(&user_id)
+            .push_channel_mention(&channel_id)
+            .push_role_mention(&role_id)
+            .push_emoji(&emoji_id)
+            .newline()
+            .push_quote("a\nb")
+            .push_codeblock("fn main() {}", Some("rust"))
+            .push_link("docs", "https://developers.revolt.chat")
+            .push_timestamp(1618932000, TimestampStyle::Relative)
+            .build();
+
+        assert_eq!(content, concat!(
+            "###### Title\n",
+            "**\\*hi\\*** *it* ~~no~~ ||secret||\n",
+            "<@01GXYZ0000000000000000000A><#01GXYZ0000000000000000000B><%01GXYZ0000000000000000000C>:01GXYZ0000000000000000000D:\n",
+            "> a\n> b\n",
+            "```rust\nfn main() {}\n```\n",
+            "[docs](https://developers.revolt.chat)<t:1618932000:R>",
+        ));
+    }
+}
@@ -1,34 +1,61 @@
-use std::sync::Arc;
+use std::{sync::Arc, time::{Duration, Instant}};
+
+use tokio::{select, sync::{Notify, RwLock}, task::JoinHandle, time::timeout};
+use tracing::Instrument;
 
 use async_channel::RecvError;
 
-use crate::{websocket::{SocketError, SocketClient}, cache::Cache, http::client::HttpClient, models::{user::User, events::server::ServerEvent, message::PartialMessage}};
+use crate::{websocket::{SocketError, SocketClient}, cache::Cache, framework::StandardFramework, metrics::Metrics, http::prelude::{HttpClient, HttpClientBuilderError}, models::{member::Member, events::server::{ServerEvent, ServerError}, id::{ChannelId, MessageId}}};
 
-use self::{event_handler::EventHandler, builder::RevoltClientBuilder, context::Context, harmony_error::HarmonyError};
+use self::{event_handler::EventHandler, builder::RevoltClientBuilder, context::Context, harmony_error::HarmonyError, data::TypeMap, handle::ClientHandle};
 
 pub mod builder;
 pub mod context;
 pub mod event_handler;
 pub mod colors;
+pub mod markdown;
+pub mod data;
+pub mod handle;
 pub mod harmony_error;
+pub mod typing;
 
 type Result<T> = std::result::Result<T, RevoltClientError>;
 
-#[derive(Debug)]
+/// How long `RevoltClient::relogin` waits for the server to accept the new token
+const AUTHENTICATION_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Represents an error that occurred while running a `RevoltClient`
+#[derive(Debug, thiserror::Error)]
 pub enum RevoltClientError {
+    #[error("No token was provided")]
     TokenMissing,
-    SocketError(SocketError),
-    RecvError(RecvError)
+    #[error("Websocket error: {0}")]
+    SocketError(#[from] SocketError),
+    #[error("Could not receive event: {0}")]
+    RecvError(#[from] RecvError),
+    #[error("Could not build the HTTP client: {0}")]
+    HttpClientError(#[from] HttpClientBuilderError),
+    #[error("The session was invalidated")]
+    SessionInvalidated,
+    #[error("The server did not respond to the authentication")]
+    AuthenticationTimeout,
 }
 
 #[derive(Clone)]
 pub struct RevoltClient {
-    pub cache: Cache,
+    pub cache: Arc<RwLock<Cache>>,
     pub http: HttpClient,
     pub socket: SocketClient,
     pub event_handler: Option<Arc<dyn EventHandler>>,
+    pub framework: Option<Arc<StandardFramework>>,
+    /// State shared with every handler, see `RevoltClientBuilder::with_data`
+    pub data: Arc<RwLock<TypeMap>>,
+    /// Metrics collected by the client, see `RevoltClientBuilder::with_metrics_endpoint`
+    pub metrics: Arc<Metrics>,
     
+    metrics_exporter: Option<Arc<JoinHandle<()>>>,
     token: Option<String>,
+    shutdown: Arc<Notify>,
 }
 
 impl RevoltClient {
@@ -36,234 +63,472 @@ impl RevoltClient {
         RevoltClientBuilder::new()
     }
 
+    /// Creates a cloneable handle to the client, for use outside of event handlers
+    pub fn handle(&self) -> ClientHandle {
+        ClientHandle {
+            http: self.http.clone(),
+            socket_sender: self.socket.client_sender.clone(),
+            cache: self.cache.clone(),
+            data: self.data.clone(),
+            metrics: self.metrics.clone(),
+            shutdown: self.shutdown.clone(),
+        }
+    }
+
     pub async fn login(&mut self) -> Result<()> {
-        if let Some(token) = &self.token {
-            if let Err(socket_err) = self.socket.authenticate(&token).await {
-                Err(RevoltClientError::SocketError(socket_err))
-            } else {
-                Ok(())
+        let token = self.token.as_ref().ok_or(RevoltClientError::TokenMissing)?;
+
+        self.socket.authenticate(token).await?;
+
+        tracing::info!("sent authentication");
+
+        Ok(())
+    }
+
+    /// Logs in again with a new token, e.g. after the previous session was invalidated.
+    ///
+    /// The server closes the connection of an invalidated session, so this reconnects to the
+    /// websocket and waits until the new token is accepted. Handles created with
+    /// [`RevoltClient::handle`] before this keep sending typing events to the old connection.
+    pub async fn relogin(&mut self, token: &str) -> Result<()> {
+        self.http = self.http.with_token(token).await?;
+        self.token = Some(token.to_string());
+
+        tracing::info!("logging in with a new token");
+
+        self.socket = self.socket.reconnect().await?;
+        self.metrics.record_connection();
+
+        self.login().await?;
+
+        timeout(AUTHENTICATION_TIMEOUT, self.wait_for_authentication()).await
+            .map_err(|_| RevoltClientError::AuthenticationTimeout)?
+    }
+
+    /// Waits for the server's reply to an authentication, skipping anything received before it
+    async fn wait_for_authentication(&mut self) -> Result<()> {
+        loop {
+            match self.socket.server_receiver.recv().await? {
+                Ok(ServerEvent::Authenticated) => {
+                    tracing::info!("authenticated");
+
+                    return Ok(());
+                },
+                Ok(ServerEvent::Error { error }) => {
+                    tracing::warn!(?error, "authentication failed");
+
+                    return Err(RevoltClientError::SessionInvalidated);
+                },
+                Ok(server_event) => tracing::debug!(event_type = server_event.name(), "skipping event received before authenticating"),
+                Err(socket_err) => tracing::warn!(%socket_err, "received a socket error while authenticating"),
             }
-        } else {
-            Err(RevoltClientError::TokenMissing)
         }
     }
 
     pub async fn listen(&mut self) -> Result<()> {
-        while let Ok(socket_result) = self.socket.server_receiver.recv().await {
+        while let Some(socket_result) = self.next_event().await {
+            if let Ok(server_event) = &socket_result {
+                self.metrics.record_event(server_event.name());
+
+                if let ServerEvent::Pong(_) = server_event {
+                    if let Some(latency) = self.socket.since_last_ping() {
+                        self.metrics.record_heartbeat(latency);
+                    }
+                }
+            }
+
             // event dispatching or something if there's no EventHandler set
 
             let http_res = self.http.get_self().await;
-            
-            let mut curr_user = User::default();
-    
-            match http_res {
-                Ok(user) => {
-                    curr_user = user.clone()
-                },
+
+            let curr_user = match http_res {
+                Ok(user) => Some(user),
                 Err(http_err) => {
-                    println!("http error: {http_err:#?}")
+                    tracing::warn!(%http_err, "could not fetch the current user");
+
+                    None
                 }
-            }
+            };
+
+            let empty_cache = self.cache.read().await.empty_copy();
+
+            let mut ctx = Context {
+                client: self.clone(),
+                user: curr_user,
+                server: None,
+                channel: None,
+                message: None,
+
+                cache: self.cache.read().await.clone(),
+                http: self.http.clone(),
+                data: self.data.clone(),
+                updated_cache: empty_cache.clone(),
+                session_invalidated: None,
+            };
+            
+            match socket_result {
+                Ok(server_event) => {
+                    let event_type = server_event.name();
+                    let start = Instant::now();
+
+                    let span = tracing::info_span!(
+                        "event",
+                        event_type,
+                        channel_id = tracing::field::Empty,
+                        server_id = tracing::field::Empty,
+                    );
+
+                    if let Err(err) = handle_events(self.event_handler.as_ref(), &mut ctx, server_event).instrument(span).await {
+                        tracing::error!(%err, "could not handle event");
+                    }
+
+                    self.metrics.record_handler_duration(event_type, start.elapsed());
+
+                    let session_invalidated = ctx.session_invalidated.take();
+
+                    if ctx.updated_cache != empty_cache {
+                        let mut cache = self.cache.write().await;
+
+                        for (id, user) in ctx.updated_cache.users {
+                            cache.users.entry(id)
+                                .and_modify(|usr| *usr = user.clone())
+                                .or_insert(user);
+                        }
+
+                        for (id, channel) in ctx.updated_cache.channels {
+                            cache.channels.entry(id)
+                                .and_modify(|chnl| *chnl = channel.clone())
+                                .or_insert(channel);
+                        }
+
+                        for (id, server) in ctx.updated_cache.servers {
+                            cache.servers.entry(id)
+                                .and_modify(|srvr| *srvr = server.clone())
+                                .or_insert(server);
+                        }
 
-            if let Some(event_handler) = &self.event_handler {
-                let mut ctx = Context {
-                    client: self.clone(),
-                    user: curr_user,
-                    server: None,
-                    channel: None,
-                    message: None,
-
-                    cache: self.cache.clone(),
-                    http: self.http.clone(),
-                    updated_cache: Cache::new(self.cache.max_messages),
-                };
-                
-                match socket_result {
-                    Ok(server_event) => {
-                        if let Err(err) = handle_events(event_handler, &mut ctx, server_event).await {
-                            println!("err: {err:#?}");
+                        for (id, message) in ctx.updated_cache.messages {
+                            cache.messages.entry(id)
+                                .and_modify(|msg| *msg = message.clone())
+                                .or_insert(message);
                         }
 
-                        if ctx.updated_cache != Cache::new(self.cache.max_messages) {
-                            for (id, user) in ctx.updated_cache.users {
-                                self.cache.users.entry(id)
-                                    .and_modify(|usr| *usr = user.clone())
-                                    .or_insert(user);
-                            }
-
-                            for (id, channel) in ctx.updated_cache.channels {
-                                self.cache.channels.entry(id)
-                                    .and_modify(|chnl| *chnl = channel.clone())
-                                    .or_insert(channel);
-                            }
-
-                            for (id, server) in ctx.updated_cache.servers {
-                                self.cache.servers.entry(id)
-                                    .and_modify(|srvr| *srvr = server.clone())
-                                    .or_insert(server);
-                            }
-
-                            for (id, message) in ctx.updated_cache.messages {
-                                self.cache.messages.entry(id)
-                                    .and_modify(|msg| *msg = message.clone())
-                                    .or_insert(message);
-                            }
+                        for (id, member) in ctx.updated_cache.members {
+                            cache.members.insert(id, member);
                         }
-                    },
-                    Err(socket_err) => {
+
+                        for revision in ctx.updated_cache.message_history.into_values().flatten() {
+                            cache.add_revision(revision);
+                        }
+
+                        self.metrics.record_cache_sizes(&cache);
+                    }
+
+                    if let Some(new_token) = session_invalidated {
+                        let relogin = match new_token {
+                            Some(token) => self.relogin(&token).await,
+                            None => Err(RevoltClientError::SessionInvalidated),
+                        };
+
+                        if let Err(err) = relogin {
+                            tracing::warn!(%err, "shutting down");
+
+                            self.socket.close();
+                            break;
+                        }
+                    }
+                },
+                Err(socket_err) => {
+                    tracing::warn!(%socket_err, "received a socket error");
+
+                    if let Some(event_handler) = &self.event_handler {
                         event_handler.socket_error(&mut ctx, socket_err).await
                     }
                 }
             }
         }
 
+        if let Some(metrics_exporter) = &self.metrics_exporter {
+            metrics_exporter.abort();
+        }
+
         Ok(())
     }
+
+    /// Waits for the next event from the socket, or returns `None`
+    /// once the client has been shut down
+    async fn next_event(&mut self) -> Option<std::result::Result<ServerEvent, SocketError>> {
+        select! {
+            socket_result = self.socket.server_receiver.recv() => socket_result.ok(),
+            _ = self.shutdown.notified() => {
+                tracing::info!("shutting down");
+
+                self.socket.close();
+
+                None
+            }
+        }
+    }
 }
 
-async fn handle_events(event_handler: &Arc<dyn EventHandler>, ctx: &mut Context, server_event: ServerEvent) -> std::result::Result<(), HarmonyError> {
+async fn handle_events(event_handler: Option<&Arc<dyn EventHandler>>, ctx: &mut Context, server_event: ServerEvent) -> std::result::Result<(), HarmonyError> {
+    // calls the event handler's method for the event, if there is a handler
+    macro_rules! dispatch {
+        ($method:ident($($arg:expr),*)) => {
+            if let Some(event_handler) = event_handler {
+                event_handler.$method(ctx, $($arg),*).await
+            }
+        };
+    }
+
     match server_event {
-        ServerEvent::Error { error } => event_handler.server_error(ctx, error).await,
-        ServerEvent::Authenticated => event_handler.authenticated(ctx).await,
+        ServerEvent::Error { error } => {
+            if let ServerError::InvalidSession = error {
+                invalidate_session(event_handler, ctx).await;
+            }
+
+            dispatch!(server_error(error))
+        },
+        ServerEvent::Authenticated => {
+            tracing::info!("authenticated");
+
+            dispatch!(authenticated())
+        },
         ServerEvent::Bulk(bulk) => {
             /*for event in bulk.events.clone() {
                 handle_events(event_handler, ctx, event).await;
             }*/
 
-            event_handler.bulk(ctx, bulk).await
+            dispatch!(bulk(bulk))
         },
-        ServerEvent::Pong(pong) => event_handler.pong(ctx, pong).await,
-        ServerEvent::Ready(ready) => event_handler.ready(ctx, ready).await,
-        ServerEvent::Message(message) => {
-            if let Err(err) = set_context_data(ctx, &message.channel, Some(&message.id)).await {
-                return Err(err);
+        ServerEvent::Pong(pong) => dispatch!(pong(pong)),
+        ServerEvent::Ready(ready) => {
+            for member in &ready.members {
+                ctx.cache.add_member(member.clone());
+                ctx.updated_cache.add_member(member.clone());
             }
 
+            dispatch!(ready(ready))
+        },
+        ServerEvent::Message(message) => {
+            set_context_data(ctx, &message.channel, Some(&message.id)).await?;
+
             ctx.cache.add_message(message.clone());
             ctx.updated_cache.add_message(message.clone());
 
-            event_handler.message_received(ctx, message).await
+            dispatch!(message_received(message.clone()));
+
+            if let Some(framework) = ctx.client.framework.clone() {
+                framework.dispatch(ctx, &message).await;
+            }
         },
         ServerEvent::MessageUpdate(msg_update) => {
-            if let Err(err) = set_context_data(ctx, &msg_update.channel, Some(&msg_update.id)).await {
-                return Err(err);
-            }
+            set_context_data(ctx, &msg_update.channel, Some(&msg_update.id)).await?;
+
+            let old = ctx.message.clone();
+            let new = old.clone().map(|mut msg| {
+                msg.apply(msg_update.data.clone());
+                msg
+            });
 
-            // add orig msg to the cache update, then update its content
-            ctx.updated_cache.add_message(ctx.message.clone().unwrap());
-            ctx.updated_cache.update_message(&msg_update.id.clone(), msg_update.data.clone());
+            if let (Some(old), Some(new)) = (&old, &new) {
+                ctx.cache.add_revision(old.clone());
+                ctx.updated_cache.add_revision(old.clone());
+
+                ctx.cache.add_message(new.clone());
+                ctx.updated_cache.add_message(new.clone());
+                ctx.message = Some(new.clone());
+            }
 
-            event_handler.message_updated(ctx, msg_update.data).await
+            dispatch!(message_updated(msg_update, old, new))
         },
         ServerEvent::MessageAppend(append) => {
-            if let Err(err) = set_context_data(ctx, &append.channel, Some(&append.message)).await {
-                return Err(err);
-            }
+            set_context_data(ctx, &append.channel, Some(&append.message)).await?;
 
-            let partial = PartialMessage {
-                embeds: append.clone().append.embeds,
-                ..Default::default()
-            };
+            let old = ctx.message.clone();
+            let new = old.clone().map(|mut msg| {
+                if let Some(embeds) = append.append.embeds.clone() {
+                    msg.embeds.extend(embeds);
+                }
+
+                msg
+            });
 
-            // add orig msg to the cache update, then update its content
-            ctx.updated_cache.add_message(ctx.message.clone().unwrap());
-            ctx.updated_cache.update_message(&append.message, partial);
+            if let (Some(old), Some(new)) = (&old, &new) {
+                ctx.cache.add_revision(old.clone());
+                ctx.updated_cache.add_revision(old.clone());
 
-            event_handler.embed_append(ctx, append).await
+                ctx.cache.add_message(new.clone());
+                ctx.updated_cache.add_message(new.clone());
+                ctx.message = Some(new.clone());
+            }
+
+            dispatch!(embed_append(append, old, new))
         },
         ServerEvent::MessageDelete(msg_delete) => {
-            if let Err(err) = set_context_data(ctx, &msg_delete.channel, Some(&msg_delete.id)).await {
-                return Err(err);
-            }
+            set_context_data(ctx, &msg_delete.channel, Some(&msg_delete.id)).await?;
+
+            ctx.cache.remove_message(&msg_delete.id);
+            ctx.updated_cache.remove_message(&msg_delete.id);
+            ctx.client.cache.write().await.remove_message(&msg_delete.id);
 
-            let msg = ctx.message.clone().unwrap();
+            let msg = ctx.message.clone();
 
-            event_handler.message_deleted(ctx, msg).await
+            dispatch!(message_deleted(msg_delete, msg))
         },
         ServerEvent::MessageReact(msg_react) => {
-            if let Err(err) = set_context_data(ctx, &msg_react.channel_id, Some(&msg_react.id)).await {
-                return Err(err);
+            set_context_data(ctx, &msg_react.channel_id, Some(&msg_react.id)).await?;
+
+            dispatch!(message_reacted(msg_react))
+        },
+        ServerEvent::MessageUnreact(msg_react) => dispatch!(message_unreacted(msg_react)),
+        ServerEvent::MessageRemoveReaction(react_remove) => dispatch!(message_react_removed(react_remove)),
+        ServerEvent::ChannelCreate(channel) => dispatch!(channel_created(channel)),
+        ServerEvent::ChannelUpdate(channel_event) => dispatch!(channel_updated(channel_event)),
+        ServerEvent::ChannelDelete { id } => dispatch!(channel_deleted(id)),
+        ServerEvent::ChannelGroupJoin(channel_event) => dispatch!(user_joined_group(channel_event)),
+        ServerEvent::ChannelGroupLeave(channel_event) => dispatch!(user_left_group(channel_event)),
+        ServerEvent::ChannelStartTyping(channel_event) => dispatch!(user_started_typing(channel_event)),
+        ServerEvent::ChannelStopTyping(channel_event) => dispatch!(user_stopped_typing(channel_event)),
+        ServerEvent::ChannelAck(acknowlege_event) => dispatch!(messages_acknowledged(acknowlege_event)),
+        ServerEvent::ServerCreate(server) => dispatch!(server_created(server)),
+        ServerEvent::ServerUpdate(server_event) => dispatch!(server_updated(server_event)),
+        ServerEvent::ServerDelete { id } => dispatch!(server_deleted(id)),
+        ServerEvent::ServerMemberUpdate(member_update) => {
+            let old = ctx.cache.get_member(&member_update.id.server, &member_update.id.user);
+            let new = old.clone().map(|mut member| {
+                member.apply(member_update.data.clone(), &member_update.clear);
+                member
+            });
+
+            if let Some(new) = &new {
+                ctx.cache.add_member(new.clone());
+                ctx.updated_cache.add_member(new.clone());
             }
 
-            event_handler.message_reacted(ctx, msg_react).await
+            dispatch!(member_updated(member_update, old, new))
         },
-        ServerEvent::MessageUnreact(msg_react) => event_handler.message_unreacted(ctx, msg_react).await,
-        ServerEvent::MessageRemoveReaction(react_remove) => event_handler.message_react_removed(ctx, react_remove).await,
-        ServerEvent::ChannelCreate(channel) => event_handler.channel_created(ctx, channel).await,
-        ServerEvent::ChannelUpdate(channel_event) => event_handler.channel_updated(ctx, channel_event).await,
-        ServerEvent::ChannelDelete { id } => event_handler.channel_deleted(ctx, id).await,
-        ServerEvent::ChannelGroupJoin(channel_event) => event_handler.user_joined_group(ctx, channel_event).await,
-        ServerEvent::ChannelGroupLeave(channel_event) => event_handler.user_left_group(ctx, channel_event).await,
-        ServerEvent::ChannelStartTyping(channel_event) => event_handler.user_started_typing(ctx, channel_event).await,
-        ServerEvent::ChannelStopTyping(channel_event) => event_handler.user_stopped_typing(ctx, channel_event).await,
-        ServerEvent::ChannelAck(acknowlege_event) => event_handler.messages_acknowledged(ctx, acknowlege_event).await,
-        ServerEvent::ServerCreate(server) => event_handler.server_created(ctx, server).await,
-        ServerEvent::ServerUpdate(server_event) => event_handler.server_updated(ctx, server_event).await,
-        ServerEvent::ServerDelete { id } => event_handler.server_deleted(ctx, id).await,
-        ServerEvent::ServerMemberUpdate(member_update) => event_handler.member_updated(ctx, member_update).await,
         ServerEvent::ServerMemberJoin(member_event) => {
-            if member_event.user == ctx.user.id {
-                println!("WOW");
-            }
+            if ctx.is_current_user(&member_event.user) {
+                tracing::info!(server_id = %member_event.id, "joined server");
+
+                let server = ctx.server(&member_event.id).await?;
 
-            event_handler.member_joined(ctx, member_event).await
+                for channel_id in &server.channels {
+                    if let Err(err) = ctx.channel(channel_id).await {
+                        tracing::warn!(%channel_id, %err, "could not fetch channel of joined server");
+                    }
+                }
+
+                ctx.server = Some(server.clone());
+
+                dispatch!(server_joined(server))
+            } else {
+                let member = Member::new(&member_event.id, &member_event.user);
+
+                ctx.cache.add_member(member.clone());
+                ctx.updated_cache.add_member(member);
+
+                dispatch!(member_joined(member_event))
+            }
         },
         ServerEvent::ServerMemberLeave(member_event) => {
             // Decide if we (the current signed in user) left the server,
             // or someone else
 
-            if member_event.user == ctx.user.id {
-                // remove server from the cache
-                // fire left_guild event
+            if ctx.is_current_user(&member_event.user) {
+                tracing::info!(server_id = %member_event.id, reason = ?member_event.reason, "removed from server");
+
+                let server = ctx.cache.remove_server(&member_event.id);
+                ctx.updated_cache.remove_server(&member_event.id);
+                ctx.client.cache.write().await.remove_server(&member_event.id);
+
+                dispatch!(server_left(member_event.id, server, member_event.reason))
             } else {
-                event_handler.member_left(ctx, member_event).await
+                ctx.cache.remove_member(&member_event.id, &member_event.user);
+                ctx.updated_cache.remove_member(&member_event.id, &member_event.user);
+                ctx.client.cache.write().await.remove_member(&member_event.id, &member_event.user);
+
+                dispatch!(member_left(member_event))
             }
         },
-        ServerEvent::ServerRoleUpdate(role_update) => event_handler.role_updated(ctx, role_update).await,
-        ServerEvent::ServerRoleDelete(role_event) => event_handler.role_deleted(ctx, role_event).await,
-        ServerEvent::UserUpdate(user_update) => event_handler.user_updated(ctx, user_update).await,
-        ServerEvent::UserRelationship(relationship_update) => event_handler.relationship_update(ctx, relationship_update).await,
-        ServerEvent::UserPlatformWipe(wipe_event) => event_handler.user_wiped_from_platform(ctx, wipe_event).await,
-        ServerEvent::EmojiCreate(emoji) => event_handler.emoji_created(ctx, emoji).await,
-        ServerEvent::EmojiDelete { id } => event_handler.emoji_deleted(ctx, id).await,
-        ServerEvent::Auth(auth_event) => {
-            // TODO
-            /*match auth_event.clone() {
-                AuthEvent::DeleteSession(session) => {
-                    session.
-                },
-                AuthEvent::DeleteAllSessions(sessions) => {
+        ServerEvent::ServerRoleUpdate(role_update) => dispatch!(role_updated(role_update)),
+        ServerEvent::ServerRoleDelete(role_event) => dispatch!(role_deleted(role_event)),
+        ServerEvent::UserUpdate(user_update) => {
+            let old = ctx.cache.get_user(&user_update.id);
+            let new = old.clone().map(|mut user| {
+                user.apply(user_update.data.clone(), &user_update.clear);
+                user
+            });
+
+            if let Some(new) = &new {
+                ctx.cache.users.insert(new.id.clone(), new.clone());
+                ctx.updated_cache.users.insert(new.id.clone(), new.clone());
+            }
+
+            if let Some(curr_user) = ctx.user.as_mut().filter(|curr_user| curr_user.id == user_update.id) {
+                curr_user.apply(user_update.data.clone(), &user_update.clear);
+            }
 
+            let presence_update = user_update.get_presence_update();
+
+            dispatch!(user_updated(user_update, old, new));
+
+            if let Some(presence_update) = presence_update {
+                dispatch!(presence_updated(presence_update))
+            }
+        },
+        ServerEvent::UserRelationship(relationship_update) => dispatch!(relationship_update(relationship_update)),
+        ServerEvent::UserPlatformWipe(wipe_event) => dispatch!(user_wiped_from_platform(wipe_event)),
+        ServerEvent::EmojiCreate(emoji) => dispatch!(emoji_created(emoji)),
+        ServerEvent::EmojiDelete { id } => dispatch!(emoji_deleted(id)),
+        ServerEvent::Auth(auth_event) => {
+            // the current user can't be fetched once our own session is gone
+            let is_self = ctx.user.as_ref().is_none_or(|curr_user| auth_event.get_user_id() == curr_user.id);
+
+            if is_self {
+                // the event doesn't say which session is ours, so check if the token still works
+                if let Err(http_err) = ctx.http.get_self().await {
+                    if http_err.is_invalid_session() {
+                        invalidate_session(event_handler, ctx).await;
+                    }
                 }
-            };*/
+            }
 
-            event_handler.auth(ctx, auth_event).await
+            dispatch!(auth(auth_event))
+        },
+        _ => {
+            if let Some(event_handler) = event_handler {
+                event_handler.received_unhandled_event().await
+            }
         },
-        _ => event_handler.received_unhandled_event().await,
     };
 
     Ok(())
 }
 
-async fn set_context_data(ctx: &mut Context, channel: &str, message: Option<&str>) -> std::result::Result<(), HarmonyError> {
-    let channel_res = ctx.channel(&channel).await;
+async fn invalidate_session(event_handler: Option<&Arc<dyn EventHandler>>, ctx: &mut Context) {
+    tracing::warn!("session invalidated");
 
-    if let Err(harmony_err) = channel_res {
-        return Err(harmony_err);
-    }
+    let new_token = match event_handler {
+        Some(event_handler) => event_handler.session_invalidated(ctx).await,
+        None => None,
+    };
+
+    ctx.session_invalidated = Some(new_token);
+}
+
+async fn set_context_data(ctx: &mut Context, channel: &ChannelId, message: Option<&MessageId>) -> std::result::Result<(), HarmonyError> {
+    let channel = ctx.channel(channel).await?;
 
-    let channel = channel_res.unwrap();
+    tracing::Span::current().record("channel_id", channel.get_id().as_str());
 
     ctx.channel = Some(channel.clone());
 
     if let Some(channel) = channel.get_text_channel() {
-        let server_res = ctx.server(&channel.get_server_id()).await;
-
-        if let Err(harmony_err) = server_res {
-            return Err(harmony_err);
-        }
+        let server = ctx.server(&channel.get_server_id()).await?;
 
-        let server = server_res.unwrap();
+        tracing::Span::current().record("server_id", server.id.as_str());
 
         ctx.server = Some(server.clone());
     }
@@ -0,0 +1,70 @@
+use std::time::Duration;
+
+use async_channel::Sender;
+use tokio::{task::JoinHandle, time::sleep};
+
+use crate::models::{events::client::ClientEvent, id::ChannelId};
+
+/// How often the typing indicator is sent again, so Revolt doesn't expire it
+pub const TYPING_REFRESH_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Shows the typing indicator in a channel for as long as it is held.
+///
+/// The indicator is refreshed every [`TYPING_REFRESH_INTERVAL`] while a long operation runs,
+/// and `EndTyping` is sent when the guard is dropped (or [`Typing::stop`] is called).
+///
+/// ```no_run
+/// # use harmony::{client::context::Context, models::channel::Channel};
+/// # async fn generate_reply() -> String { String::new() }
+/// # async fn run(ctx: &Context, channel: &Channel) {
+/// let typing = channel.start_typing(ctx);
+/// let reply = generate_reply().await;
+/// drop(typing);
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Typing {
+    channel_id: ChannelId,
+    sender: Sender<ClientEvent>,
+    task: JoinHandle<()>,
+}
+
+impl Typing {
+    pub fn start(sender: Sender<ClientEvent>, channel_id: &ChannelId) -> Self {
+        let task = tokio::spawn(Self::refresh(sender.clone(), channel_id.clone()));
+
+        Self {
+            channel_id: channel_id.clone(),
+            sender,
+            task,
+        }
+    }
+
+    pub fn get_channel_id(&self) -> ChannelId {
+        self.channel_id.clone()
+    }
+
+    /// Stops showing the typing indicator
+    pub fn stop(self) { }
+
+    async fn refresh(sender: Sender<ClientEvent>, channel_id: ChannelId) {
+        loop {
+            let event = ClientEvent::BeginTyping { channel_id: channel_id.clone() };
+
+            if sender.send(event).await.is_err() {
+                break;
+            }
+
+            sleep(TYPING_REFRESH_INTERVAL).await;
+        }
+    }
+}
+
+impl Drop for Typing {
+    fn drop(&mut self) {
+        self.task.abort();
+
+        // the socket channel is unbounded, so this only fails once the client is closed
+        let _ = self.sender.try_send(ClientEvent::EndTyping { channel_id: self.channel_id.clone() });
+    }
+}
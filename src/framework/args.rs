@@ -0,0 +1,247 @@
+use std::str::FromStr;
+
+use crate::{client::context::Context, models::{user::User, channel::Channel, server::Role, content::parse_mention, id::{ChannelId, RoleId, UserId}}};
+
+/// Represents an error that occurred while parsing a command's arguments
+#[derive(Debug, thiserror::Error, Clone, PartialEq)]
+pub enum ArgError {
+    #[error("Not enough arguments were provided")]
+    Eos,
+    #[error("Could not parse argument `{0}`")]
+    Parse(String),
+    #[error("Argument `{0}` has an unclosed quote")]
+    UnclosedQuote(String),
+    #[error("Could not find a user matching `{0}`")]
+    UnknownUser(String),
+    #[error("Could not find a channel matching `{0}`")]
+    UnknownChannel(String),
+    #[error("Could not find a role matching `{0}`")]
+    UnknownRole(String),
+}
+
+/// The arguments passed to a command, split on whitespace.
+///
+/// Arguments wrapped in double quotes are treated as a single argument,
+/// so `!say "hello world" 2` yields `hello world` and `2`. A backslash
+/// escapes the next character, e.g. `\"` for a literal quote.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Args {
+    message: String,
+    args: Vec<String>,
+    offset: usize,
+}
+
+impl Args {
+    pub fn new(message: &str) -> Result<Self, ArgError> {
+        Ok(Self {
+            message: message.trim().to_string(),
+            args: split_args(message)?,
+            offset: 0,
+        })
+    }
+
+    /// The unparsed argument string
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// How many arguments have not yet been consumed
+    pub fn remaining(&self) -> usize {
+        self.args.len() - self.offset
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    /// Returns the next argument without consuming it
+    pub fn current(&self) -> Option<&str> {
+        self.args.get(self.offset).map(|arg| arg.as_str())
+    }
+
+    /// Consumes the next argument and parses it into `T`
+    pub fn single<T: FromStr>(&mut self) -> Result<T, ArgError> {
+        let arg = self.current().ok_or(ArgError::Eos)?;
+
+        let parsed = arg.parse::<T>()
+            .map_err(|_| ArgError::Parse(arg.to_string()))?;
+
+        self.offset += 1;
+
+        Ok(parsed)
+    }
+
+    /// Consumes the next argument if it parses into `T`, leaving it in place otherwise
+    pub fn optional<T: FromStr>(&mut self) -> Option<T> {
+        self.single::<T>().ok()
+    }
+
+    /// Consumes all remaining arguments, joined by a single space
+    pub fn rest(&mut self) -> String {
+        let rest = self.args[self.offset..].join(" ");
+        self.offset = self.args.len();
+
+        rest
+    }
+
+    /// Consumes the next argument as a user mention (`<@id>`) or a raw user id,
+    /// resolving it against the cache
+    pub async fn user(&mut self, ctx: &mut Context) -> Result<User, ArgError> {
+        let arg = self.current().ok_or(ArgError::Eos)?.to_string();
+        let id = parse_mention(&arg, "<@").unwrap_or(&arg);
+
+        let user_id = UserId::new(id)
+            .map_err(|_| ArgError::UnknownUser(arg.clone()))?;
+
+        let user = ctx.user(&user_id).await
+            .map_err(|_| ArgError::UnknownUser(arg.clone()))?;
+
+        self.offset += 1;
+
+        Ok(user)
+    }
+
+    /// Consumes the next argument as a channel mention (`<#id>`) or a raw channel id,
+    /// resolving it against the cache
+    pub async fn channel(&mut self, ctx: &mut Context) -> Result<Channel, ArgError> {
+        let arg = self.current().ok_or(ArgError::Eos)?.to_string();
+        let id = parse_mention(&arg, "<#").unwrap_or(&arg);
+
+        let channel_id = ChannelId::new(id)
+            .map_err(|_| ArgError::UnknownChannel(arg.clone()))?;
+
+        let channel = ctx.channel(&channel_id).await
+            .map_err(|_| ArgError::UnknownChannel(arg.clone()))?;
+
+        self.offset += 1;
+
+        Ok(channel)
+    }
+
+    /// Consumes the next argument as a role mention (`<%id>`), a raw role id or a role name,
+    /// resolving it against the server the command was used in.
+    ///
+    /// Returns the role's id along with the role.
+    pub fn role(&mut self, ctx: &Context) -> Result<(RoleId, Role), ArgError> {
+        let arg = self.current().ok_or(ArgError::Eos)?.to_string();
+        let id = parse_mention(&arg, "<%").unwrap_or(&arg);
+
+        let server = ctx.server.as_ref()
+            .ok_or_else(|| ArgError::UnknownRole(arg.clone()))?;
+
+        let role = server.roles.get_key_value(id)
+            .or_else(|| server.roles.iter().find(|(_, role)| role.name.eq_ignore_ascii_case(id)))
+            .map(|(id, role)| (id.clone(), role.clone()))
+            .ok_or_else(|| ArgError::UnknownRole(arg.clone()))?;
+
+        self.offset += 1;
+
+        Ok(role)
+    }
+}
+
+fn split_args(message: &str) -> Result<Vec<String>, ArgError> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut escaped = false;
+
+    for ch in message.trim().chars() {
+        if escaped {
+            current.push(ch);
+            escaped = false;
+
+            continue;
+        }
+
+        match ch {
+            '\\' => escaped = true,
+            '"' if in_quotes => {
+                args.push(std::mem::take(&mut current));
+                in_quotes = false;
+            },
+            '"' if current.is_empty() => in_quotes = true,
+            ch if ch.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            },
+            ch => current.push(ch),
+        }
+    }
+
+    if escaped {
+        current.push('\\');
+    }
+
+    if in_quotes {
+        return Err(ArgError::UnclosedQuote(current));
+    }
+
+    if !current.is_empty() {
+        args.push(current);
+    }
+
+    Ok(args)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(message: &str) -> Vec<String> {
+        split_args(message).unwrap()
+    }
+
+    #[test]
+    fn splits_on_whitespace() {
+        assert_eq!(args("  one two\tthree  "), ["one", "two", "three"]);
+        assert!(args("   ").is_empty());
+    }
+
+    #[test]
+    fn keeps_quoted_arguments_together() {
+        assert_eq!(args(r#"say "hello world" 2"#), ["say", "hello world", "2"]);
+        assert_eq!(args(r#""" empty"#), ["", "empty"]);
+    }
+
+    #[test]
+    fn quotes_inside_words_are_literal() {
+        assert_eq!(args(r#"it"s fine"#), [r#"it"s"#, "fine"]);
+    }
+
+    #[test]
+    fn backslash_escapes_the_next_character() {
+        assert_eq!(args(r#""say \"hi\"" now"#), [r#"say "hi""#, "now"]);
+        assert_eq!(args(r#"a\ b c"#), ["a b", "c"]);
+        assert_eq!(args(r#"back\\slash"#), [r#"back\slash"#]);
+        assert_eq!(args(r#"trailing\"#), [r#"trailing\"#]);
+    }
+
+    #[test]
+    fn unterminated_quote_is_an_error() {
+        assert_eq!(split_args(r#"say "hello world"#), Err(ArgError::UnclosedQuote("hello world".to_string())));
+        assert_eq!(split_args(r#""escaped \""#), Err(ArgError::UnclosedQuote(r#"escaped ""#.to_string())));
+    }
+
+    #[test]
+    fn consumes_arguments_in_order() {
+        let mut args = Args::new(r#"3 "two words" rest of it"#).unwrap();
+
+        assert_eq!(args.remaining(), 5);
+        assert_eq!(args.single::<u32>(), Ok(3));
+        assert_eq!(args.optional::<u32>(), None);
+        assert_eq!(args.single::<String>(), Ok("two words".to_string()));
+        assert_eq!(args.rest(), "rest of it");
+        assert!(args.is_empty());
+        assert_eq!(args.single::<String>(), Err(ArgError::Eos));
+    }
+
+    #[test]
+    fn failed_parse_keeps_the_argument() {
+        let mut args = Args::new("abc").unwrap();
+
+        assert_eq!(args.single::<u32>(), Err(ArgError::Parse("abc".to_string())));
+        assert_eq!(args.current(), Some("abc"));
+    }
+}
@@ -0,0 +1,140 @@
+use std::{collections::{HashMap, VecDeque}, sync::{Arc, Mutex}, time::{Duration, Instant}};
+
+use crate::models::{message::Message, id::ServerId};
+
+/// What a [`Bucket`]'s uses are counted against
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BucketType {
+    User,
+    Channel,
+    /// Falls back to the channel when used outside of a server
+    Server,
+    Global,
+}
+
+/// A rate limit allowing `limit` uses every `period`, tracked separately per user,
+/// channel or server depending on its [`BucketType`].
+///
+/// Clones share their usage, so a bucket can be given to several commands
+/// to rate limit them together.
+#[derive(Debug, Clone)]
+pub struct Bucket {
+    pub bucket_type: BucketType,
+    pub limit: usize,
+    pub period: Duration,
+
+    uses: Arc<Mutex<HashMap<String, VecDeque<Instant>>>>,
+}
+
+impl Bucket {
+    pub fn new(bucket_type: BucketType, limit: usize, period: Duration) -> Self {
+        Self {
+            bucket_type,
+            limit,
+            period,
+            uses: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// A cooldown allowing a single use every `period`
+    pub fn cooldown(bucket_type: BucketType, period: Duration) -> Self {
+        Self::new(bucket_type, 1, period)
+    }
+
+    /// Records a use of the bucket for the author of `msg`.
+    ///
+    /// If the bucket is exhausted, the use isn't recorded and the time
+    /// until the next use is available is returned instead.
+    pub fn take(&self, msg: &Message, server_id: Option<&ServerId>) -> Result<(), Duration> {
+        let key = match self.bucket_type {
+            BucketType::User => msg.author.to_string(),
+            BucketType::Channel => msg.channel.to_string(),
+            BucketType::Server => server_id.map_or(msg.channel.as_str(), ServerId::as_str).to_string(),
+            BucketType::Global => String::new(),
+        };
+
+        let now = Instant::now();
+        let mut uses = self.uses.lock().unwrap();
+
+        // forget keys whose uses have all expired, so the map doesn't keep growing
+        uses.retain(|_, key_uses| key_uses.back().is_some_and(|used| now.duration_since(*used) < self.period));
+
+        let key_uses = uses.entry(key).or_default();
+
+        while key_uses.front().is_some_and(|used| now.duration_since(*used) >= self.period) {
+            key_uses.pop_front();
+        }
+
+        if key_uses.len() >= self.limit {
+            let oldest = key_uses.front().copied().unwrap_or(now);
+
+            return Err(self.period.saturating_sub(now.duration_since(oldest)));
+        }
+
+        key_uses.push_back(now);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{thread, time::Duration};
+
+    use crate::models::message::Message;
+
+    use super::{Bucket, BucketType};
+
+    fn message(author: &str) -> Message {
+        serde_json::from_value(serde_json::json!({
+            "_id": "01GXYZ0000000000000000000A",
+            "channel": "01GXYZ0000000000000000000B",
+            "author": author,
+            "content": "!ping",
+        })).unwrap()
+    }
+
+    #[test]
+    fn limits_uses_per_key() {
+        let bucket = Bucket::new(BucketType::User, 2, Duration::from_secs(60));
+        let first = message("01GXYZ0000000000000000000C");
+        let second = message("01GXYZ0000000000000000000D");
+
+        assert_eq!(bucket.take(&first, None), Ok(()));
+        assert_eq!(bucket.take(&first, None), Ok(()));
+
+        let remaining = bucket.take(&first, None).unwrap_err();
+        assert!(remaining > Duration::from_secs(59) && remaining <= Duration::from_secs(60));
+
+        assert_eq!(bucket.take(&second, None), Ok(()));
+    }
+
+    #[test]
+    fn clones_share_their_uses() {
+        let bucket = Bucket::cooldown(BucketType::Global, Duration::from_secs(60));
+        let msg = message("01GXYZ0000000000000000000C");
+
+        assert_eq!(bucket.clone().take(&msg, None), Ok(()));
+        assert!(bucket.take(&message("01GXYZ0000000000000000000D"), None).is_err());
+    }
+
+    #[test]
+    fn refills_after_the_period() {
+        let bucket = Bucket::new(BucketType::Channel, 2, Duration::from_millis(200));
+        let msg = message("01GXYZ0000000000000000000C");
+
+        assert_eq!(bucket.take(&msg, None), Ok(()));
+        thread::sleep(Duration::from_millis(120));
+        assert_eq!(bucket.take(&msg, None), Ok(()));
+        assert!(bucket.take(&msg, None).is_err());
+
+        // only the first use has expired, so a single use is available again
+        thread::sleep(Duration::from_millis(120));
+        assert_eq!(bucket.take(&msg, None), Ok(()));
+        assert!(bucket.take(&msg, None).is_err());
+
+        thread::sleep(Duration::from_millis(220));
+        assert_eq!(bucket.take(&msg, None), Ok(()));
+        assert_eq!(bucket.take(&msg, None), Ok(()));
+    }
+}
@@ -0,0 +1,144 @@
+use std::time::Duration;
+
+use crate::{client::{context::Context, harmony_error::HarmonyError}, models::{message::Message, channel::Channel, Permission, id::UserId}};
+
+/// A requirement that has to be met before a command runs
+#[derive(Debug, Clone, PartialEq)]
+pub enum Check {
+    /// The author needs all of these permissions in the channel the command was used in
+    RequirePermissions(Vec<Permission>),
+    /// Only the framework's owners (or the bot's owner, when none are set) may use the command
+    OwnerOnly,
+    ServerOnly,
+    DmOnly,
+    NsfwOnly,
+}
+
+/// The kind of [`CheckError`], used to configure the reply sent when a check fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CheckKind {
+    RequirePermissions,
+    OwnerOnly,
+    ServerOnly,
+    DmOnly,
+    NsfwOnly,
+    Cooldown,
+}
+
+/// Represents a check that failed
+#[derive(Debug, thiserror::Error)]
+pub enum CheckError {
+    #[error("You are missing the following permissions: {0:?}")]
+    MissingPermissions(Vec<Permission>),
+    /// The author's member couldn't be fetched to compute their permissions
+    #[error("Could not fetch your permissions: {0}")]
+    MemberUnavailable(#[source] HarmonyError),
+    #[error("This command can only be used by the bot's owners")]
+    OwnerOnly,
+    #[error("This command can only be used in a server")]
+    ServerOnly,
+    #[error("This command can only be used in DMs")]
+    DmOnly,
+    #[error("This command can only be used in NSFW channels")]
+    NsfwOnly,
+    #[error("This command is on cooldown, try again in {}s", cooldown_secs(.0))]
+    Cooldown(Duration),
+}
+
+impl CheckError {
+    pub fn kind(&self) -> CheckKind {
+        match self {
+            Self::MissingPermissions(_) | Self::MemberUnavailable(_) => CheckKind::RequirePermissions,
+            Self::OwnerOnly => CheckKind::OwnerOnly,
+            Self::ServerOnly => CheckKind::ServerOnly,
+            Self::DmOnly => CheckKind::DmOnly,
+            Self::NsfwOnly => CheckKind::NsfwOnly,
+            Self::Cooldown(_) => CheckKind::Cooldown,
+        }
+    }
+}
+
+/// The whole seconds left on a cooldown, rounded up so it's never shown as over early
+pub(crate) fn cooldown_secs(remaining: &Duration) -> u128 {
+    remaining.as_millis().div_ceil(1000)
+}
+
+impl Check {
+    pub async fn run(&self, ctx: &mut Context, msg: &Message, owners: &[UserId]) -> Result<(), CheckError> {
+        match self {
+            Check::RequirePermissions(required) => {
+                let permissions = author_permissions(ctx, msg).await?;
+
+                let missing = required.iter()
+                    .filter(|perm| !perm.is_granted(permissions))
+                    .copied()
+                    .collect::<Vec<_>>();
+
+                if missing.is_empty() {
+                    Ok(())
+                } else {
+                    Err(CheckError::MissingPermissions(missing))
+                }
+            },
+            Check::OwnerOnly => {
+                let is_owner = if owners.is_empty() {
+                    ctx.user.as_ref()
+                        .and_then(|user| user.bot.as_ref())
+                        .is_some_and(|bot| bot.owner == msg.author)
+                } else {
+                    owners.contains(&msg.author)
+                };
+
+                if is_owner { Ok(()) } else { Err(CheckError::OwnerOnly) }
+            },
+            Check::ServerOnly => {
+                if ctx.server.is_some() { Ok(()) } else { Err(CheckError::ServerOnly) }
+            },
+            Check::DmOnly => {
+                let is_dm = ctx.channel.as_ref().is_some_and(|channel| channel.is_dm());
+
+                if is_dm { Ok(()) } else { Err(CheckError::DmOnly) }
+            },
+            Check::NsfwOnly => {
+                let is_nsfw = match &ctx.channel {
+                    Some(Channel::TextChannel(channel)) => channel.is_nsfw(),
+                    Some(Channel::VoiceChannel(channel)) => channel.is_nsfw(),
+                    Some(Channel::Group(channel)) => channel.is_nsfw(),
+                    _ => false,
+                };
+
+                if is_nsfw { Ok(()) } else { Err(CheckError::NsfwOnly) }
+            },
+        }
+    }
+}
+
+/// Computes the permissions of the message's author in the channel it was sent in
+async fn author_permissions(ctx: &mut Context, msg: &Message) -> Result<u64, CheckError> {
+    let server = ctx.server.clone().ok_or(CheckError::ServerOnly)?;
+
+    let member = ctx.member(&server.id, &msg.author).await
+        .map_err(CheckError::MemberUnavailable)?;
+
+    let permissions = match &ctx.channel {
+        Some(channel) => server.channel_permissions(&msg.author, &member.roles, channel),
+        None => server.member_permissions(&msg.author, &member.roles),
+    };
+
+    Ok(permissions)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{cooldown_secs, CheckError};
+
+    #[test]
+    fn rounds_cooldowns_up() {
+        assert_eq!(cooldown_secs(&Duration::from_millis(1)), 1);
+        assert_eq!(cooldown_secs(&Duration::from_secs(2)), 2);
+        assert_eq!(cooldown_secs(&Duration::from_millis(2001)), 3);
+        assert_eq!(CheckError::Cooldown(Duration::from_secs(5)).to_string(), "This command is on cooldown, try again in 5s");
+    }
+}
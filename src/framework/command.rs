@@ -0,0 +1,156 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{client::{context::Context, harmony_error::HarmonyError}, http::prelude::HttpError, models::message::Message};
+
+use super::{args::{Args, ArgError}, checks::Check, bucket::Bucket};
+
+pub type CommandResult = Result<(), CommandError>;
+
+/// Represents an error returned from a command
+#[derive(Debug, thiserror::Error)]
+pub enum CommandError {
+    #[error(transparent)]
+    ArgError(#[from] ArgError),
+    #[error(transparent)]
+    HttpError(#[from] HttpError),
+    #[error(transparent)]
+    HarmonyError(#[from] HarmonyError),
+    #[error("{0}")]
+    Other(String),
+}
+
+impl From<&str> for CommandError {
+    fn from(err: &str) -> Self {
+        Self::Other(err.to_string())
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(err: String) -> Self {
+        Self::Other(err)
+    }
+}
+
+/// The code that runs when a command is invoked
+#[async_trait]
+pub trait CommandHandler: Send + Sync {
+    async fn run(&self, ctx: &mut Context, msg: &Message, args: Args) -> CommandResult;
+}
+
+#[derive(Clone)]
+pub struct Command {
+    pub name: String,
+    pub aliases: Vec<String>,
+    pub description: Option<String>,
+    pub usage: Option<String>,
+    pub checks: Vec<Check>,
+    pub bucket: Option<Bucket>,
+    pub handler: Arc<dyn CommandHandler>,
+}
+
+impl Command {
+    pub fn new<T: CommandHandler + 'static>(name: &str, handler: T) -> Self {
+        Self {
+            name: name.to_string(),
+            aliases: Vec::new(),
+            description: None,
+            usage: None,
+            checks: Vec::new(),
+            bucket: None,
+            handler: Arc::new(handler),
+        }
+    }
+
+    pub fn with_alias(mut self, alias: &str) -> Self {
+        self.aliases.push(alias.to_string());
+
+        self
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+
+        self
+    }
+
+    /// Sets the usage shown in the help command, e.g. `<user> [reason]`
+    pub fn with_usage(mut self, usage: &str) -> Self {
+        self.usage = Some(usage.to_string());
+
+        self
+    }
+
+    pub fn with_check(mut self, check: Check) -> Self {
+        self.checks.push(check);
+
+        self
+    }
+
+    /// Rate limits the command with `bucket`, e.g. `Bucket::cooldown(BucketType::User, Duration::from_secs(5))`
+    pub fn with_bucket(mut self, bucket: Bucket) -> Self {
+        self.bucket = Some(bucket);
+
+        self
+    }
+
+    /// Checks if `name` is this command's name or one of its aliases
+    pub fn is_named(&self, name: &str, case_insensitive: bool) -> bool {
+        std::iter::once(&self.name)
+            .chain(self.aliases.iter())
+            .any(|n| if case_insensitive { n.eq_ignore_ascii_case(name) } else { n == name })
+    }
+}
+
+/// A named set of commands, optionally invoked under a shared prefix
+/// (e.g. `!mod kick` for a group with the prefix `mod`)
+#[derive(Clone)]
+pub struct CommandGroup {
+    pub name: String,
+    pub description: Option<String>,
+    pub prefixes: Vec<String>,
+    /// Checks that apply to every command in the group
+    pub checks: Vec<Check>,
+    pub commands: Vec<Command>,
+}
+
+impl CommandGroup {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            description: None,
+            prefixes: Vec::new(),
+            checks: Vec::new(),
+            commands: Vec::new(),
+        }
+    }
+
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = Some(description.to_string());
+
+        self
+    }
+
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefixes.push(prefix.to_string());
+
+        self
+    }
+
+    pub fn with_check(mut self, check: Check) -> Self {
+        self.checks.push(check);
+
+        self
+    }
+
+    pub fn with_command(mut self, command: Command) -> Self {
+        self.commands.push(command);
+
+        self
+    }
+
+    pub fn get_command(&self, name: &str, case_insensitive: bool) -> Option<&Command> {
+        self.commands.iter().find(|cmd| cmd.is_named(name, case_insensitive))
+    }
+}
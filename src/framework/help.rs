@@ -0,0 +1,67 @@
+use super::command::{Command, CommandGroup};
+
+/// Builds the help message listing every command in `groups`
+pub fn help_all(groups: &[CommandGroup], prefix: &str) -> String {
+    let mut help = String::from("**Commands**\n");
+
+    for group in groups {
+        help.push_str(&format!("\n__{}__", group.name));
+
+        if let Some(description) = &group.description {
+            help.push_str(&format!(" - {description}"));
+        }
+
+        help.push('\n');
+
+        for command in &group.commands {
+            help.push_str(&format!("`{prefix}{}`", invocation(group, command)));
+
+            if let Some(description) = &command.description {
+                help.push_str(&format!(" - {description}"));
+            }
+
+            help.push('\n');
+        }
+    }
+
+    help.push_str(&format!("\nType `{prefix}help <command>` for more info on a command."));
+
+    help
+}
+
+/// Builds the help message for a single command
+pub fn help_command(group: &CommandGroup, command: &Command, prefix: &str) -> String {
+    let mut help = format!("**{}**\n", command.name);
+
+    if let Some(description) = &command.description {
+        help.push_str(&format!("{description}\n"));
+    }
+
+    let mut usage = format!("{prefix}{}", invocation(group, command));
+
+    if let Some(args) = &command.usage {
+        usage.push_str(&format!(" {args}"));
+    }
+
+    help.push_str(&format!("Usage: `{usage}`\n"));
+
+    if !command.aliases.is_empty() {
+        let aliases = command.aliases.iter()
+            .map(|alias| format!("`{alias}`"))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        help.push_str(&format!("Aliases: {aliases}\n"));
+    }
+
+    help.push_str(&format!("Group: {}", group.name));
+
+    help
+}
+
+fn invocation(group: &CommandGroup, command: &Command) -> String {
+    match group.prefixes.first() {
+        Some(group_prefix) => format!("{group_prefix} {}", command.name),
+        None => command.name.clone(),
+    }
+}
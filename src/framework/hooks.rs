@@ -0,0 +1,38 @@
+use async_trait::async_trait;
+
+use crate::{client::context::Context, models::message::Message};
+
+use super::{command::{Command, CommandError}, checks::CheckError};
+
+/// Callbacks run by the [`StandardFramework`](super::StandardFramework) around command dispatch
+#[async_trait]
+pub trait FrameworkHooks: Send + Sync {
+    /// Runs before a command is invoked. Returning `false` cancels the command.
+    async fn before(&self, _ctx: &mut Context, _msg: &Message, _command: &Command) -> bool { true }
+
+    /// Runs after a command has completed successfully
+    async fn after(&self, _ctx: &mut Context, _msg: &Message, _command: &Command) { }
+
+    /// Runs when a command returns an error
+    async fn command_error(&self, ctx: &mut Context, msg: &Message, _command: &Command, error: CommandError) {
+        let _ = ctx.http.say(&msg.channel, &format!("Error: {error}")).await;
+    }
+
+    /// Runs when one of a command's checks fails, or it is on cooldown.
+    ///
+    /// `reply` is the reply configured for the check with `StandardFramework::with_check_reply`,
+    /// or the error's message when none is set. An empty reply isn't sent.
+    async fn check_failed(&self, ctx: &mut Context, msg: &Message, _command: &Command, _error: CheckError, reply: String) {
+        if !reply.is_empty() {
+            let _ = ctx.http.say(&msg.channel, &reply).await;
+        }
+    }
+
+    /// Runs when a message starts with a prefix but doesn't match any command
+    async fn unknown_command(&self, _ctx: &mut Context, _msg: &Message, _name: &str) { }
+}
+
+/// The hooks used when none are set: errors are replied to the channel, everything else is ignored
+pub struct DefaultHooks;
+
+impl FrameworkHooks for DefaultHooks { }
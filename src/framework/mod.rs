@@ -0,0 +1,317 @@
+use std::{collections::HashMap, sync::Arc};
+
+use crate::{client::context::Context, models::{message::Message, id::UserId}};
+
+use self::{args::Args, command::{Command, CommandGroup}, hooks::{FrameworkHooks, DefaultHooks}, checks::{CheckKind, CheckError}};
+
+pub mod args;
+pub mod bucket;
+pub mod checks;
+pub mod command;
+pub mod help;
+pub mod hooks;
+
+/// A prefix based command framework.
+///
+/// Commands are organised into [`CommandGroup`]s and dispatched from received messages
+/// once the framework is added to the client with `RevoltClientBuilder::with_framework`.
+#[derive(Clone)]
+pub struct StandardFramework {
+    pub prefixes: Vec<String>,
+    pub mention_prefix: bool,
+    pub case_insensitive: bool,
+    pub ignore_bots: bool,
+    pub help_enabled: bool,
+    pub owners: Vec<UserId>,
+    pub check_replies: HashMap<CheckKind, String>,
+    pub groups: Vec<CommandGroup>,
+    pub hooks: Arc<dyn FrameworkHooks>,
+}
+
+impl Default for StandardFramework {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StandardFramework {
+    pub fn new() -> Self {
+        Self {
+            prefixes: Vec::new(),
+            mention_prefix: false,
+            case_insensitive: false,
+            ignore_bots: true,
+            help_enabled: true,
+            owners: Vec::new(),
+            check_replies: HashMap::new(),
+            groups: Vec::new(),
+            hooks: Arc::new(DefaultHooks),
+        }
+    }
+
+    pub fn with_prefix(mut self, prefix: &str) -> Self {
+        self.prefixes.push(prefix.to_string());
+
+        self
+    }
+
+    /// Allows commands to be invoked by mentioning the bot, e.g. `@bot ping`
+    pub fn with_mention_prefix(mut self, mention_prefix: bool) -> Self {
+        self.mention_prefix = mention_prefix;
+
+        self
+    }
+
+    /// Matches prefixes, group prefixes and command names regardless of case
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+
+        self
+    }
+
+    /// Sets whether messages sent by bots are ignored. Defaults to `true`
+    pub fn ignore_bots(mut self, ignore_bots: bool) -> Self {
+        self.ignore_bots = ignore_bots;
+
+        self
+    }
+
+    /// Sets whether the built-in `help` command is enabled. Defaults to `true`
+    pub fn with_help(mut self, help_enabled: bool) -> Self {
+        self.help_enabled = help_enabled;
+
+        self
+    }
+
+    /// Adds a user allowed to use `Check::OwnerOnly` commands.
+    /// When no owners are set, the bot's owner is used instead
+    pub fn with_owner(mut self, user_id: UserId) -> Self {
+        self.owners.push(user_id);
+
+        self
+    }
+
+    /// Sets the reply sent when a check of the given kind fails.
+    /// `{remaining}` is replaced with the seconds left on a cooldown,
+    /// and an empty reply silences the failure
+    pub fn with_check_reply(mut self, kind: CheckKind, reply: &str) -> Self {
+        self.check_replies.insert(kind, reply.to_string());
+
+        self
+    }
+
+    pub fn with_group(mut self, group: CommandGroup) -> Self {
+        self.groups.push(group);
+
+        self
+    }
+
+    pub fn with_hooks<T: FrameworkHooks + 'static>(mut self, hooks: T) -> Self {
+        self.hooks = Arc::new(hooks);
+
+        self
+    }
+
+    /// Finds a command by its name or alias, along with the group it belongs to
+    pub fn get_command(&self, name: &str) -> Option<(&CommandGroup, &Command)> {
+        self.groups.iter()
+            .find_map(|group| group.get_command(name, self.case_insensitive).map(|cmd| (group, cmd)))
+    }
+
+    /// Parses `msg` and runs the command it invokes, if any
+    pub async fn dispatch(&self, ctx: &mut Context, msg: &Message) {
+        let content = match &msg.content {
+            Some(content) => content,
+            None => return,
+        };
+
+        if ctx.is_current_user(&msg.author) {
+            return;
+        }
+
+        let self_id = ctx.user.as_ref().map(|user| user.id.clone());
+
+        let (prefix, rest) = match self.strip_prefix(self_id.as_ref(), content) {
+            Some(stripped) => stripped,
+            None => return,
+        };
+
+        if self.ignore_bots {
+            if let Ok(author) = ctx.user(&msg.author).await {
+                if author.bot.is_some() {
+                    return;
+                }
+            }
+        }
+
+        let (name, rest) = split_first_word(rest);
+
+        if name.is_empty() {
+            return;
+        }
+
+        let (group, command, rest) = match self.find_command(name, rest) {
+            Some(found) => found,
+            None => {
+                if self.help_enabled && self.str_eq(name, "help") {
+                    self.send_help(ctx, msg, &prefix, rest).await;
+                } else {
+                    self.hooks.unknown_command(ctx, msg, name).await;
+                }
+
+                return;
+            }
+        };
+
+        let args = match Args::new(rest) {
+            Ok(args) => args,
+            Err(arg_err) => {
+                return self.hooks.command_error(ctx, msg, command, arg_err.into()).await;
+            }
+        };
+
+        if let Err(check_err) = self.run_checks(ctx, msg, group, command).await {
+            let reply = self.check_reply(&check_err);
+
+            return self.hooks.check_failed(ctx, msg, command, check_err, reply).await;
+        }
+
+        if !self.hooks.before(ctx, msg, command).await {
+            return;
+        }
+
+        // the bucket is only taken once the command is certain to run,
+        // so commands cancelled by a check or hook don't use it up
+        if let Some(bucket) = &command.bucket {
+            let server_id = ctx.server.as_ref().map(|server| server.id.clone());
+
+            if let Err(remaining) = bucket.take(msg, server_id.as_ref()) {
+                let check_err = CheckError::Cooldown(remaining);
+                let reply = self.check_reply(&check_err);
+
+                return self.hooks.check_failed(ctx, msg, command, check_err, reply).await;
+            }
+        }
+
+        tracing::debug!(command = %command.name, author = %msg.author, "running command");
+
+        match command.handler.run(ctx, msg, args).await {
+            Ok(()) => self.hooks.after(ctx, msg, command).await,
+            Err(err) => {
+                tracing::debug!(command = %command.name, %err, "command returned an error");
+
+                self.hooks.command_error(ctx, msg, command, err).await
+            },
+        }
+    }
+
+    async fn run_checks(&self, ctx: &mut Context, msg: &Message, group: &CommandGroup, command: &Command) -> Result<(), CheckError> {
+        for check in group.checks.iter().chain(command.checks.iter()) {
+            check.run(ctx, msg, &self.owners).await?;
+        }
+
+        Ok(())
+    }
+
+    fn check_reply(&self, check_err: &CheckError) -> String {
+        match self.check_replies.get(&check_err.kind()) {
+            Some(reply) => {
+                let remaining = match check_err {
+                    CheckError::Cooldown(remaining) => checks::cooldown_secs(remaining),
+                    _ => 0,
+                };
+
+                reply.replace("{remaining}", &remaining.to_string())
+            },
+            None => check_err.to_string(),
+        }
+    }
+
+    fn find_command<'a>(&self, name: &str, rest: &'a str) -> Option<(&CommandGroup, &Command, &'a str)> {
+        for group in self.groups.iter().filter(|group| !group.prefixes.is_empty()) {
+            if group.prefixes.iter().any(|prefix| self.str_eq(prefix, name)) {
+                let (sub_name, sub_rest) = split_first_word(rest);
+
+                if let Some(command) = group.get_command(sub_name, self.case_insensitive) {
+                    return Some((group, command, sub_rest));
+                }
+            }
+        }
+
+        self.groups.iter()
+            .filter(|group| group.prefixes.is_empty())
+            .find_map(|group| group.get_command(name, self.case_insensitive).map(|cmd| (group, cmd, rest)))
+    }
+
+    /// Strips a configured prefix (or a mention of the bot) from `content`,
+    /// returning the prefix to display in help messages and the remaining content
+    fn strip_prefix<'a>(&self, self_id: Option<&UserId>, content: &'a str) -> Option<(String, &'a str)> {
+        if let Some(self_id) = self_id.filter(|_| self.mention_prefix) {
+            let mention = format!("<@{self_id}>");
+
+            if let Some(rest) = content.strip_prefix(&mention) {
+                let display = self.prefixes.first().cloned().unwrap_or(format!("{mention} "));
+
+                return Some((display, rest.trim_start()));
+            }
+        }
+
+        for prefix in &self.prefixes {
+            let start = match content.get(..prefix.len()) {
+                Some(start) => start,
+                None => continue,
+            };
+
+            if self.str_eq(start, prefix) {
+                return Some((prefix.clone(), &content[prefix.len()..]));
+            }
+        }
+
+        None
+    }
+
+    async fn send_help(&self, ctx: &mut Context, msg: &Message, prefix: &str, rest: &str) {
+        let (name, _) = split_first_word(rest);
+
+        let help = if name.is_empty() {
+            help::help_all(&self.groups, prefix)
+        } else {
+            match self.get_command(name) {
+                Some((group, command)) => help::help_command(group, command, prefix),
+                None => format!("No command named `{name}` exists."),
+            }
+        };
+
+        let _ = ctx.http.say(&msg.channel, &help).await;
+    }
+
+    fn str_eq(&self, a: &str, b: &str) -> bool {
+        if self.case_insensitive {
+            a.eq_ignore_ascii_case(b)
+        } else {
+            a == b
+        }
+    }
+}
+
+fn split_first_word(content: &str) -> (&str, &str) {
+    let content = content.trim_start();
+
+    match content.find(char::is_whitespace) {
+        Some(idx) => (&content[..idx], &content[idx..]),
+        None => (content, ""),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::split_first_word;
+
+    #[test]
+    fn splits_the_first_word() {
+        assert_eq!(split_first_word("ping"), ("ping", ""));
+        assert_eq!(split_first_word("  say hello world"), ("say", " hello world"));
+        assert_eq!(split_first_word("say\thello"), ("say", "\thello"));
+        assert_eq!(split_first_word(""), ("", ""));
+    }
+}
@@ -1,10 +1,20 @@
-use reqwest::header::{HeaderMap, HeaderValue};
+use std::sync::Arc;
+
+use reqwest::header::{HeaderMap, HeaderValue, InvalidHeaderValue};
+
+use crate::metrics::Metrics;
 
 use super::prelude::{ClientSessionType, REVOLT_API_URL, HttpClient};
 
-#[derive(Debug)]
+/// Represents an error that occurred while building Harmony's HTTP Client
+#[derive(Debug, thiserror::Error)]
 pub enum HttpClientBuilderError {
-    MissingToken
+    #[error("No token was provided")]
+    MissingToken,
+    #[error("The token is not a valid header value")]
+    InvalidToken(#[from] InvalidHeaderValue),
+    #[error("Could not build the HTTP client: {0}")]
+    ClientError(#[from] reqwest::Error),
 }
 
 #[derive(Default, Debug)]
@@ -12,6 +22,7 @@ pub struct HttpClientBuilder {
     token: Option<String>,
     api_url: String,
     client_type: ClientSessionType,
+    metrics: Option<Arc<Metrics>>,
 }
 
 impl HttpClientBuilder {
@@ -35,6 +46,13 @@ impl HttpClientBuilder {
         self
     }
 
+    /// Records requests made by the client in `metrics`
+    pub fn with_metrics(mut self, metrics: Arc<Metrics>) -> Self {
+        self.metrics = Some(metrics);
+
+        self
+    }
+
     /// sets the API url.
     pub fn with_api(mut self, api: &str) -> Self {
         self.api_url = api.to_string();
@@ -59,16 +77,17 @@ impl HttpClientBuilder {
         };
 
         let mut headers = HeaderMap::new();
-        headers.insert(session_header, HeaderValue::from_str(&token).unwrap());
+        headers.insert(session_header, HeaderValue::from_str(&token)?);
 
         let req_client = reqwest::Client::builder()
             .default_headers(headers)
-            .build()
-            .unwrap();
+            .build()?;
  
         Ok(HttpClient {
             api_url: self.api_url,
             client: req_client,
+            session_type: self.client_type,
+            metrics: self.metrics,
         })
     }
 }
\ No newline at end of file
@@ -1,16 +1,19 @@
-use reqwest::Response;
+use std::{sync::Arc, time::{Duration, Instant}};
+
+use reqwest::{Method, Response, StatusCode};
 use serde::{de::DeserializeOwned, Serialize};
+use tracing::Instrument;
 
-use crate::models::{error::RevoltError, revolt::RevoltInfo, user::User, server::Server, channel::Channel, message::{Message, PartialMessage}};
+use crate::{metrics::Metrics, models::{error::RevoltError, revolt::RevoltInfo, user::{User, UserProfile}, server::Server, channel::{Channel, CreateChannel}, message::{Message, MessageEdit, MessageQuery, PartialMessage, MessageValidationError}, member::{Ban, EditMember, Member, MemberList}, invite::{Invite, InviteInfo, InviteJoin, is_invite_code}, id::{ChannelId, MessageId, ServerId, UserId}}};
 
-use super::prelude::HttpClientBuilder;
+use super::prelude::{HttpClientBuilder, HttpClientBuilderError};
 
 /// the default Revolt api url
 pub const REVOLT_API_URL: &str = "https://api.revolt.chat";
 
 type HttpResult<T> = std::result::Result<T, HttpError>;
 
-#[derive(Default, Debug)]
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ClientSessionType {
     #[default]
     Bot,
@@ -18,16 +21,102 @@ pub enum ClientSessionType {
 }
 
 /// Represents an error that occurred within Harmony's HTTP Client
-#[derive(Debug)]
+#[derive(Debug, thiserror::Error)]
 pub enum HttpError {
-    RequestUnsuccessful(String),
-    RevoltError(RevoltError),
+    /// The request could not be sent, or its response could not be read
+    #[error("HTTP request failed: {0}")]
+    RequestUnsuccessful(#[from] reqwest::Error),
+    /// Revolt responded with an error
+    #[error("Revolt returned an error ({status}): {error}")]
+    RevoltError {
+        status: StatusCode,
+        #[source]
+        error: RevoltError,
+    },
+    /// Revolt responded with a body that could not be parsed.
+    /// `body` holds the raw response for debugging
+    #[error("Could not parse response ({status}): {source}")]
+    UnparsedResponse {
+        status: StatusCode,
+        body: String,
+        #[source]
+        source: serde_json::Error,
+    },
+    /// The message would be rejected by Revolt, so it wasn't sent
+    #[error("Invalid message: {0}")]
+    InvalidMessage(#[from] MessageValidationError),
+    /// The action can only be performed by user sessions, not bots
+    #[error("This action requires a user session")]
+    UserSessionRequired,
+    /// The invite code isn't alphanumeric, so it wasn't sent
+    #[error("`{0}` is not a valid invite code")]
+    InvalidInviteCode(String),
+    /// The channel doesn't hold messages, e.g. a voice channel
+    #[error("Channel `{0}` can't be messaged")]
+    NotMessageable(ChannelId),
+}
+
+impl HttpError {
+    /// The status code of the response, if one was received
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Self::RequestUnsuccessful(err) => err.status(),
+            Self::RevoltError { status, .. } => Some(*status),
+            Self::UnparsedResponse { status, .. } => Some(*status),
+            Self::InvalidMessage(_) | Self::UserSessionRequired | Self::InvalidInviteCode(_) | Self::NotMessageable(_) => None,
+        }
+    }
+
+    /// The error Revolt responded with, if there was one
+    pub fn revolt_error(&self) -> Option<&RevoltError> {
+        match self {
+            Self::RevoltError { error, .. } => Some(error),
+            _ => None,
+        }
+    }
+
+    /// Checks if retrying the request could succeed, i.e. the request timed out,
+    /// could not connect, was rate limited or failed because of a server error
+    pub fn is_retryable(&self) -> bool {
+        if let Self::RequestUnsuccessful(err) = self {
+            if err.is_timeout() || err.is_connect() {
+                return true;
+            }
+        }
+
+        if let Some(RevoltError::InternalError | RevoltError::DatabaseError { .. }) = self.revolt_error() {
+            return true;
+        }
+
+        self.status()
+            .is_some_and(|status| status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error())
+    }
+
+    /// Checks if the request was rejected because the session's token is no longer valid
+    pub fn is_invalid_session(&self) -> bool {
+        if let Some(error) = self.revolt_error() {
+            return matches!(error, RevoltError::InvalidSession);
+        }
+
+        self.status() == Some(StatusCode::UNAUTHORIZED)
+    }
+
+    /// Checks if the requested resource doesn't exist
+    pub fn is_not_found(&self) -> bool {
+        if let Some(error) = self.revolt_error() {
+            return error.is_not_found();
+        }
+
+        self.status() == Some(StatusCode::NOT_FOUND)
+    }
 }
 
 #[derive(Debug, Clone)]
 pub struct HttpClient {
     pub api_url: String,
     pub client: reqwest::Client,
+    pub session_type: ClientSessionType,
+    pub(crate) metrics: Option<Arc<Metrics>>,
 }
 
 impl HttpClient {
@@ -35,40 +124,98 @@ impl HttpClient {
         HttpClientBuilder::new()
     }
 
-    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> HttpResult<T> {
-        let response = self.client.get(self.as_url(path)).send().await;
-        
-        match response {
-            Ok(response) => Self::parse_response(response).await,
-            Err(e) => Err(HttpError::RequestUnsuccessful(format!("{e:#?}")))
+    /// Builds a copy of this client which authenticates with `token` instead
+    pub async fn with_token(&self, token: &str) -> Result<HttpClient, HttpClientBuilderError> {
+        let mut builder = HttpClientBuilder::new()
+            .with_api(&self.api_url)
+            .session_type(self.session_type)
+            .with_token(token);
+
+        if let Some(metrics) = &self.metrics {
+            builder = builder.with_metrics(metrics.clone());
         }
+
+        builder.build().await
+    }
+
+    pub async fn get<T: DeserializeOwned>(&self, path: &str) -> HttpResult<T> {
+        self.request::<T, ()>(Method::GET, path, None).await
     }
 
     pub async fn post<T: DeserializeOwned, U: Serialize>(&self, path: &str, body: U) -> HttpResult<T> {
-        let result = self.client.post(self.as_url(path)).json(&body).send().await;
+        self.request(Method::POST, path, Some(&body)).await
+    }
 
-        match result {
-            Ok(response) => Self::parse_response(response).await,
-            Err(e) => Err(HttpError::RequestUnsuccessful(format!("{e:#?}")))
-        }
+    pub async fn put<T: DeserializeOwned, U: Serialize>(&self, path: &str, body: U) -> HttpResult<T> {
+        self.request(Method::PUT, path, Some(&body)).await
+    }
+
+    pub async fn patch<T: DeserializeOwned, U: Serialize>(&self, path: &str, body: U) -> HttpResult<T> {
+        self.request(Method::PATCH, path, Some(&body)).await
+    }
+
+    pub async fn delete<T: DeserializeOwned>(&self, path: &str) -> HttpResult<T> {
+        self.request::<T, ()>(Method::DELETE, path, None).await
+    }
+
+    /// Sends a request, recording it (and how long a rate limit asks to wait) in the client's metrics
+    async fn request<T: DeserializeOwned, U: Serialize>(&self, method: Method, path: &str, body: Option<&U>) -> HttpResult<T> {
+        let span = tracing::debug_span!("http", %method, route = path, status = tracing::field::Empty);
+
+        async {
+            let mut request = self.client.request(method.clone(), self.as_url(path));
+
+            if let Some(body) = body {
+                request = request.json(body);
+            }
+
+            let start = Instant::now();
+            let response = request.send().await?;
+
+            if let Some(metrics) = &self.metrics {
+                metrics.record_http_request(method.as_str(), path, response.status().as_u16(), start.elapsed());
+
+                if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                    if let Some(wait) = Self::rate_limit_reset(&response) {
+                        tracing::warn!(?wait, "rate limited");
+
+                        metrics.record_rate_limit_wait(wait);
+                    }
+                }
+            }
+
+            Self::parse_response(response).await
+        }.instrument(span).await
+    }
+
+    /// How long until the rate limit resets, from the `X-RateLimit-Reset-After` header (in milliseconds)
+    fn rate_limit_reset(response: &Response) -> Option<Duration> {
+        response.headers()
+            .get("x-ratelimit-reset-after")?
+            .to_str().ok()?
+            .parse::<u64>().ok()
+            .map(Duration::from_millis)
     }
 
     async fn parse_response<T: DeserializeOwned>(response: Response) -> HttpResult<T> {
-        let text = match response.text().await {
-            Ok(text) => text,
-            Err(e) => return Err(HttpError::RequestUnsuccessful(format!("{e:#?}")))
-        };
+        let status = response.status();
+        let text = response.text().await?;
+
+        tracing::Span::current().record("status", status.as_u16());
 
         // check if there was an error
-        if let Ok(revolt_error) = serde_json::from_str::<RevoltError>(&text) {
-            return Err(HttpError::RevoltError(revolt_error));
+        if let Ok(error) = serde_json::from_str::<RevoltError>(&text) {
+            tracing::debug!(%error, "Revolt returned an error");
+
+            return Err(HttpError::RevoltError { status, error });
         };
 
+        // actions like deleting respond without a body, which is parsed as `()`
+        let text = if text.is_empty() { "null".to_string() } else { text };
+
         // error parse failed, try to parse into our desired type
-        match serde_json::from_str::<T>(&text) {
-            Ok(data) => Ok(data),
-            Err(e) => Err(HttpError::RequestUnsuccessful(format!("{e:#?}")))
-        }
+        serde_json::from_str::<T>(&text)
+            .map_err(|source| HttpError::UnparsedResponse { status, body: text, source })
     }
 
     pub async fn query_node(&self) -> HttpResult<RevoltInfo> {
@@ -80,35 +227,113 @@ impl HttpClient {
     }
 }
 
+impl AsRef<HttpClient> for HttpClient {
+    fn as_ref(&self) -> &HttpClient {
+        self
+    }
+}
+
 // === users
 impl HttpClient {
     pub async fn get_self(&self) -> HttpResult<User> {
         self.get::<User>("/users/@me").await
     }
 
-    pub async fn get_user(&self, user_id: &str) -> HttpResult<User> {
+    pub async fn get_user(&self, user_id: &UserId) -> HttpResult<User> {
         self.get::<User>(&format!("/users/{user_id}")).await
     }
+
+    pub async fn get_user_profile(&self, user_id: &UserId) -> HttpResult<UserProfile> {
+        self.get::<UserProfile>(&format!("/users/{user_id}/profile")).await
+    }
+
+    /// Opens the DM channel with a user, creating it if needed
+    pub async fn open_dm(&self, user_id: &UserId) -> HttpResult<Channel> {
+        self.get::<Channel>(&format!("/users/{user_id}/dm")).await
+    }
 }
 
 impl HttpClient {
-    pub async fn get_server(&self, server_id: &str) -> HttpResult<Server> {
+    pub async fn get_server(&self, server_id: &ServerId) -> HttpResult<Server> {
         self.get::<Server>(&format!("/servers/{server_id}")).await
     }
+
+    pub async fn get_member(&self, server_id: &ServerId, user_id: &UserId) -> HttpResult<Member> {
+        self.get::<Member>(&format!("/servers/{server_id}/members/{user_id}")).await
+    }
+
+    /// Gets every member of a server, along with their users
+    pub async fn get_members(&self, server_id: &ServerId) -> HttpResult<MemberList> {
+        self.get::<MemberList>(&format!("/servers/{server_id}/members")).await
+    }
+
+    pub async fn edit_member(&self, server_id: &ServerId, user_id: &UserId, edit: EditMember) -> HttpResult<Member> {
+        self.patch::<Member, EditMember>(&format!("/servers/{server_id}/members/{user_id}"), edit).await
+    }
+
+    pub async fn kick_member(&self, server_id: &ServerId, user_id: &UserId) -> HttpResult<()> {
+        self.delete::<()>(&format!("/servers/{server_id}/members/{user_id}")).await
+    }
+
+    pub async fn ban_member(&self, server_id: &ServerId, user_id: &UserId, reason: Option<&str>) -> HttpResult<Ban> {
+        let body = serde_json::json!({ "reason": reason });
+
+        self.put::<Ban, serde_json::Value>(&format!("/servers/{server_id}/bans/{user_id}"), body).await
+    }
+
+    pub async fn create_channel(&self, server_id: &ServerId, channel: CreateChannel) -> HttpResult<Channel> {
+        self.post::<Channel, CreateChannel>(&format!("/servers/{server_id}/channels"), channel).await
+    }
+
+    pub async fn get_invites(&self, server_id: &ServerId) -> HttpResult<Vec<Invite>> {
+        self.get::<Vec<Invite>>(&format!("/servers/{server_id}/invites")).await
+    }
 }
 
 impl HttpClient {
-    pub async fn get_channel(&self, channel_id: &str) -> HttpResult<Channel> {
+    pub async fn get_channel(&self, channel_id: &ChannelId) -> HttpResult<Channel> {
         self.get::<Channel>(&format!("/channels/{channel_id}")).await
     }
 
-    pub async fn get_message(&self, channel_id: &str, message_id: &str) -> HttpResult<Message> {
+    pub async fn get_message(&self, channel_id: &ChannelId, message_id: &MessageId) -> HttpResult<Message> {
         self.get::<Message>(&format!("/channels/{channel_id}/messages/{message_id}")).await
     }
+
+    /// Creates an invite to a server channel or group
+    pub async fn create_invite(&self, channel_id: &ChannelId) -> HttpResult<Invite> {
+        self.request::<Invite, ()>(Method::POST, &format!("/channels/{channel_id}/invites"), None).await
+    }
 }
 
+// === invites
 impl HttpClient {
-    pub async fn say(&self, channel_id: &str, msg: &str) -> HttpResult<Message> {
+    pub async fn get_invite(&self, code: &str) -> HttpResult<InviteInfo> {
+        check_invite_code(code)?;
+
+        self.get::<InviteInfo>(&format!("/invites/{code}")).await
+    }
+
+    pub async fn delete_invite(&self, code: &str) -> HttpResult<()> {
+        check_invite_code(code)?;
+
+        self.delete::<()>(&format!("/invites/{code}")).await
+    }
+
+    /// Joins the server or group an invite is for. Bots can't join through invites,
+    /// so this fails with [`HttpError::UserSessionRequired`] for bot sessions
+    pub async fn join_invite(&self, code: &str) -> HttpResult<InviteJoin> {
+        if self.session_type == ClientSessionType::Bot {
+            return Err(HttpError::UserSessionRequired);
+        }
+
+        check_invite_code(code)?;
+
+        self.request::<InviteJoin, ()>(Method::POST, &format!("/invites/{code}"), None).await
+    }
+}
+
+impl HttpClient {
+    pub async fn say(&self, channel_id: &ChannelId, msg: &str) -> HttpResult<Message> {
         let msg = PartialMessage {
             content: Some(msg.to_string()),
             ..Default::default()
@@ -116,7 +341,94 @@ impl HttpClient {
 
         self.send_msg_in_channel(channel_id, msg).await
     }
-    pub async fn send_msg_in_channel(&self, channel_id: &str, msg: PartialMessage) -> HttpResult<Message> {
+
+    pub async fn send_msg_in_channel(&self, channel_id: &ChannelId, msg: PartialMessage) -> HttpResult<Message> {
+        msg.validate()?;
+
         self.post::<Message, PartialMessage>(&format!("/channels/{channel_id}/messages"), msg).await
     }
-}
\ No newline at end of file
+
+    pub async fn edit_message(&self, channel_id: &ChannelId, message_id: &MessageId, edit: MessageEdit) -> HttpResult<Message> {
+        edit.validate()?;
+
+        self.patch::<Message, MessageEdit>(&format!("/channels/{channel_id}/messages/{message_id}"), edit).await
+    }
+
+    pub async fn delete_message(&self, channel_id: &ChannelId, message_id: &MessageId) -> HttpResult<()> {
+        self.delete::<()>(&format!("/channels/{channel_id}/messages/{message_id}")).await
+    }
+
+    /// Fetches messages from a channel's history
+    pub async fn get_messages(&self, channel_id: &ChannelId, query: &MessageQuery) -> HttpResult<Vec<Message>> {
+        self.get::<Vec<Message>>(&format!("/channels/{channel_id}/messages?{}", query.to_query_string())).await
+    }
+
+    /// Deletes up to [`MAX_MESSAGE_BATCH`](crate::models::message::MAX_MESSAGE_BATCH) messages at once.
+    /// Revolt only allows this for messages less than a week old
+    pub async fn delete_messages(&self, channel_id: &ChannelId, message_ids: &[MessageId]) -> HttpResult<()> {
+        let body = serde_json::json!({ "ids": message_ids });
+
+        self.request::<(), serde_json::Value>(Method::DELETE, &format!("/channels/{channel_id}/messages/bulk"), Some(&body)).await
+    }
+
+    /// Reacts to a message with a unicode emoji or the id of a custom emoji
+    pub async fn add_reaction(&self, channel_id: &ChannelId, message_id: &MessageId, emoji: &str) -> HttpResult<()> {
+        self.request::<(), ()>(Method::PUT, &reaction_route(channel_id, message_id, emoji), None).await
+    }
+
+    /// Removes the client's own reaction from a message
+    pub async fn remove_reaction(&self, channel_id: &ChannelId, message_id: &MessageId, emoji: &str) -> HttpResult<()> {
+        self.delete::<()>(&reaction_route(channel_id, message_id, emoji)).await
+    }
+}
+
+/// Rejects codes that would change the path they're put in, e.g. `../users/@me`
+fn check_invite_code(code: &str) -> HttpResult<()> {
+    if is_invite_code(code) {
+        Ok(())
+    } else {
+        Err(HttpError::InvalidInviteCode(code.to_string()))
+    }
+}
+
+/// Builds the route of a reaction, percent-encoding the emoji so characters like `#` in `#️⃣` stay in the path
+fn reaction_route(channel_id: &ChannelId, message_id: &MessageId, emoji: &str) -> String {
+    let mut route = format!("/channels/{channel_id}/messages/{message_id}/reactions/");
+
+    for byte in emoji.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            route.push(byte as char);
+        } else {
+            route.push_str(&format!("%{byte:02X}"));
+        }
+    }
+
+    route
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::id::{ChannelId, MessageId};
+
+    use super::{check_invite_code, reaction_route, HttpError};
+
+    #[test]
+    fn rejects_invite_codes_that_change_the_path() {
+        assert!(check_invite_code("Testers").is_ok());
+
+        for code in ["", "../users/@me", "Testers?x=1", "Testers/", "Test ers"] {
+            assert!(matches!(check_invite_code(code), Err(HttpError::InvalidInviteCode(invalid)) if invalid == code));
+        }
+    }
+
+    #[test]
+    fn percent_encodes_reaction_emoji() {
+        let channel_id = ChannelId::new("01FD58YK5W7QRV5H3D64NTEB4K").unwrap();
+        let message_id = MessageId::new("01FD58YK5W7QRV5H3D64NTEB4K").unwrap();
+        let route = |emoji| reaction_route(&channel_id, &message_id, emoji);
+
+        assert_eq!(route("01GX9Y4W2TCBWNEQ7XCMJ2KBMW"), "/channels/01FD58YK5W7QRV5H3D64NTEB4K/messages/01FD58YK5W7QRV5H3D64NTEB4K/reactions/01GX9Y4W2TCBWNEQ7XCMJ2KBMW");
+        assert!(route("#\u{fe0f}\u{20e3}").ends_with("/reactions/%23%EF%B8%8F%E2%83%A3"));
+        assert!(route("../?x=1").ends_with("/reactions/..%2F%3Fx%3D1"));
+    }
+}
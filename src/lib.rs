@@ -2,4 +2,11 @@ pub mod http;
 pub mod models;
 pub mod client;
 pub mod websocket;
-pub mod cache;
\ No newline at end of file
+pub mod cache;
+pub mod framework;
+pub mod metrics;
+
+pub use client::harmony_error::HarmonyError as Error;
+
+/// A `Result` using Harmony's error type
+pub type Result<T> = std::result::Result<T, Error>;
@@ -0,0 +1,66 @@
+use std::sync::Arc;
+
+use tokio::{io::{AsyncReadExt, AsyncWriteExt}, net::{TcpListener, ToSocketAddrs}, task::JoinHandle};
+
+use super::Metrics;
+
+/// Binds to `addr` and serves `metrics` in Prometheus' text exposition format
+/// to every request, regardless of its path
+pub async fn serve<A: ToSocketAddrs>(metrics: Arc<Metrics>, addr: A) -> std::io::Result<JoinHandle<()>> {
+    let listener = TcpListener::bind(addr).await?;
+
+    tracing::info!(addr = ?listener.local_addr().ok(), "serving metrics");
+
+    Ok(tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = match listener.accept().await {
+                Ok(conn) => conn,
+                Err(err) => {
+                    tracing::warn!(%err, "could not accept metrics connection");
+                    continue;
+                }
+            };
+
+            let metrics = metrics.clone();
+
+            tokio::spawn(async move {
+                // the request itself doesn't matter, but it has to be read before responding
+                let mut buf = [0; 1024];
+                let _ = stream.read(&mut buf).await;
+
+                let _ = stream.write_all(response(&metrics).as_bytes()).await;
+                let _ = stream.shutdown().await;
+            });
+        }
+    }))
+}
+
+/// Renders `metrics` as a complete HTTP response
+fn response(metrics: &Metrics) -> String {
+    let body = metrics.render();
+
+    format!(
+        "HTTP/1.1 200 OK\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{response, Metrics};
+
+    #[test]
+    fn responds_with_the_rendered_metrics() {
+        let metrics = Metrics::new();
+        metrics.record_event("Ready");
+
+        let response = response(&metrics);
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(head.contains("Content-Type: text/plain; version=0.0.4"));
+        assert!(head.contains(&format!("Content-Length: {}", body.len())));
+        assert_eq!(body, metrics.render());
+        assert!(body.contains("harmony_events_total{type=\"Ready\"} 1\n"));
+    }
+}
@@ -0,0 +1,292 @@
+use std::{collections::BTreeMap, fmt::Write, sync::{Mutex, atomic::{AtomicU64, Ordering}}, time::Duration};
+
+use crate::cache::Cache;
+
+pub mod exporter;
+
+/// The upper bounds (in seconds) of the histogram buckets
+const BUCKETS: [f64; 12] = [0.001, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0];
+
+#[derive(Debug, Clone, Default)]
+struct Histogram {
+    counts: [u64; BUCKETS.len()],
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn observe(&mut self, duration: Duration) {
+        let secs = duration.as_secs_f64();
+
+        for (count, bound) in self.counts.iter_mut().zip(BUCKETS) {
+            if secs <= bound {
+                *count += 1;
+            }
+        }
+
+        self.sum += secs;
+        self.count += 1;
+    }
+
+    fn render(&self, out: &mut String, name: &str, labels: &str) {
+        let sep = if labels.is_empty() { "" } else { "," };
+
+        for (count, bound) in self.counts.iter().zip(BUCKETS) {
+            let _ = writeln!(out, "{name}_bucket{{{labels}{sep}le=\"{bound}\"}} {count}");
+        }
+
+        let _ = writeln!(out, "{name}_bucket{{{labels}{sep}le=\"+Inf\"}} {}", self.count);
+        let labels = if labels.is_empty() { String::new() } else { format!("{{{labels}}}") };
+
+        let _ = writeln!(out, "{name}_sum{labels} {}", self.sum);
+        let _ = writeln!(out, "{name}_count{labels} {}", self.count);
+    }
+}
+
+/// A registry of metrics collected by the client.
+///
+/// Available as `RevoltClient::metrics`, and rendered in Prometheus' text exposition
+/// format with [`Metrics::render`] or served with `RevoltClientBuilder::with_metrics_endpoint`.
+#[derive(Debug, Default)]
+pub struct Metrics {
+    events: Mutex<BTreeMap<String, u64>>,
+    handler_durations: Mutex<BTreeMap<String, Histogram>>,
+    http_requests: Mutex<BTreeMap<(String, String, u16), u64>>,
+    http_durations: Mutex<BTreeMap<String, Histogram>>,
+    rate_limit_waits: AtomicU64,
+    rate_limit_wait_millis: AtomicU64,
+    connections: AtomicU64,
+    heartbeat_latency: Mutex<Histogram>,
+    cache_sizes: Mutex<BTreeMap<&'static str, usize>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a received event
+    pub fn record_event(&self, event_type: &str) {
+        *self.events.lock().unwrap().entry(event_type.to_string()).or_default() += 1;
+    }
+
+    /// Records how long the handlers for an event took to run
+    pub fn record_handler_duration(&self, event_type: &str, duration: Duration) {
+        self.handler_durations.lock().unwrap()
+            .entry(event_type.to_string())
+            .or_default()
+            .observe(duration);
+    }
+
+    /// Records a completed HTTP request. `route` is labelled by its template, e.g. `/channels/{id}`
+    pub fn record_http_request(&self, method: &str, route: &str, status: u16, duration: Duration) {
+        let route = normalize_route(route);
+
+        *self.http_requests.lock().unwrap()
+            .entry((method.to_string(), route.to_string(), status))
+            .or_default() += 1;
+
+        self.http_durations.lock().unwrap()
+            .entry(route.to_string())
+            .or_default()
+            .observe(duration);
+    }
+
+    /// Records a rate limited request, along with how long the API asked to wait before retrying
+    pub fn record_rate_limit_wait(&self, wait: Duration) {
+        self.rate_limit_waits.fetch_add(1, Ordering::Relaxed);
+        self.rate_limit_wait_millis.fetch_add(wait.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Records a successful connection to the websocket
+    pub fn record_connection(&self) {
+        self.connections.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Records the time between a heartbeat and its response
+    pub fn record_heartbeat(&self, latency: Duration) {
+        self.heartbeat_latency.lock().unwrap().observe(latency);
+    }
+
+    /// Updates the cache size gauges
+    pub fn record_cache_sizes(&self, cache: &Cache) {
+        let mut sizes = self.cache_sizes.lock().unwrap();
+
+        sizes.insert("users", cache.users.len());
+        sizes.insert("channels", cache.channels.len());
+        sizes.insert("servers", cache.servers.len());
+        sizes.insert("messages", cache.messages.len());
+        sizes.insert("members", cache.members.len());
+    }
+
+    /// How many times the websocket has reconnected
+    pub fn reconnects(&self) -> u64 {
+        self.connections.load(Ordering::Relaxed).saturating_sub(1)
+    }
+
+    /// Renders every metric in Prometheus' text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+
+        out.push_str("# HELP harmony_events_total Events received from the websocket.\n");
+        out.push_str("# TYPE harmony_events_total counter\n");
+        for (event_type, count) in self.events.lock().unwrap().iter() {
+            let _ = writeln!(out, "harmony_events_total{{type=\"{event_type}\"}} {count}");
+        }
+
+        out.push_str("# HELP harmony_handler_duration_seconds Time spent handling events.\n");
+        out.push_str("# TYPE harmony_handler_duration_seconds histogram\n");
+        for (event_type, histogram) in self.handler_durations.lock().unwrap().iter() {
+            histogram.render(&mut out, "harmony_handler_duration_seconds", &format!("type=\"{event_type}\""));
+        }
+
+        out.push_str("# HELP harmony_http_requests_total HTTP requests made to the API.\n");
+        out.push_str("# TYPE harmony_http_requests_total counter\n");
+        for ((method, route, status), count) in self.http_requests.lock().unwrap().iter() {
+            let _ = writeln!(out, "harmony_http_requests_total{{method=\"{method}\",route=\"{route}\",status=\"{status}\"}} {count}");
+        }
+
+        out.push_str("# HELP harmony_http_request_duration_seconds Time taken by HTTP requests.\n");
+        out.push_str("# TYPE harmony_http_request_duration_seconds histogram\n");
+        for (route, histogram) in self.http_durations.lock().unwrap().iter() {
+            histogram.render(&mut out, "harmony_http_request_duration_seconds", &format!("route=\"{route}\""));
+        }
+
+        out.push_str("# HELP harmony_rate_limit_waits_total Requests rejected by a rate limit.\n");
+        out.push_str("# TYPE harmony_rate_limit_waits_total counter\n");
+        let _ = writeln!(out, "harmony_rate_limit_waits_total {}", self.rate_limit_waits.load(Ordering::Relaxed));
+
+        out.push_str("# HELP harmony_rate_limit_wait_seconds_total Time rate limited requests were asked to wait.\n");
+        out.push_str("# TYPE harmony_rate_limit_wait_seconds_total counter\n");
+        let wait_secs = self.rate_limit_wait_millis.load(Ordering::Relaxed) as f64 / 1000.0;
+        let _ = writeln!(out, "harmony_rate_limit_wait_seconds_total {wait_secs}");
+
+        out.push_str("# HELP harmony_reconnects_total Times the websocket has reconnected.\n");
+        out.push_str("# TYPE harmony_reconnects_total counter\n");
+        let _ = writeln!(out, "harmony_reconnects_total {}", self.reconnects());
+
+        out.push_str("# HELP harmony_heartbeat_latency_seconds Time between a heartbeat and its response.\n");
+        out.push_str("# TYPE harmony_heartbeat_latency_seconds histogram\n");
+        self.heartbeat_latency.lock().unwrap().render(&mut out, "harmony_heartbeat_latency_seconds", "");
+
+        out.push_str("# HELP harmony_cache_size Objects held in the cache.\n");
+        out.push_str("# TYPE harmony_cache_size gauge\n");
+        for (kind, size) in self.cache_sizes.lock().unwrap().iter() {
+            let _ = writeln!(out, "harmony_cache_size{{kind=\"{kind}\"}} {size}");
+        }
+
+        out
+    }
+}
+
+/// The routes requested by `HttpClient`, placeholders matching any single segment.
+/// Literal routes come before the templates they'd otherwise match, e.g. `/users/@me`
+const ROUTES: [&str; 18] = [
+    "/",
+    "/users/@me",
+    "/users/{id}",
+    "/users/{id}/dm",
+    "/users/{id}/profile",
+    "/channels/{id}",
+    "/channels/{id}/invites",
+    "/channels/{id}/messages",
+    "/channels/{id}/messages/bulk",
+    "/channels/{id}/messages/{id}",
+    "/channels/{id}/messages/{id}/reactions/{emoji}",
+    "/invites/{code}",
+    "/servers/{id}",
+    "/servers/{id}/bans/{id}",
+    "/servers/{id}/channels",
+    "/servers/{id}/invites",
+    "/servers/{id}/members",
+    "/servers/{id}/members/{id}",
+];
+
+/// Routes that don't match any of [`ROUTES`] share this label, so they can't leak into it
+const UNKNOWN_ROUTE: &str = "{unknown}";
+
+/// Finds the template of a route so each route is a single label value,
+/// e.g. `/channels/01H.../messages?limit=50` becomes `/channels/{id}/messages`
+fn normalize_route(route: &str) -> &'static str {
+    let path = route.split('?').next().unwrap_or_default();
+
+    ROUTES.into_iter()
+        .find(|template| matches_template(template, path))
+        .unwrap_or(UNKNOWN_ROUTE)
+}
+
+fn matches_template(template: &str, path: &str) -> bool {
+    let mut segments = path.split('/');
+
+    template.split('/').all(|expected| match segments.next() {
+        Some(segment) if expected.starts_with('{') => !segment.is_empty(),
+        Some(segment) => segment == expected,
+        None => false,
+    }) && segments.next().is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::{normalize_route, Metrics, UNKNOWN_ROUTE};
+
+    #[test]
+    fn normalizes_routes_by_template() {
+        assert_eq!(normalize_route("/"), "/");
+        assert_eq!(normalize_route("/users/@me"), "/users/@me");
+        assert_eq!(normalize_route("/users/01GXYZ0000000000000000000A"), "/users/{id}");
+        assert_eq!(normalize_route("/channels/01GXYZ0000000000000000000A/messages?limit=50&before=01GXYZ0000000000000000000B"), "/channels/{id}/messages");
+        assert_eq!(normalize_route("/channels/01GXYZ0000000000000000000A/messages/bulk"), "/channels/{id}/messages/bulk");
+        assert_eq!(normalize_route("/servers/01GXYZ0000000000000000000A/members/01GXYZ0000000000000000000B"), "/servers/{id}/members/{id}");
+    }
+
+    #[test]
+    fn keeps_codes_and_emoji_out_of_routes() {
+        assert_eq!(normalize_route("/invites/Testers"), "/invites/{code}");
+        assert_eq!(
+            normalize_route("/channels/01GXYZ0000000000000000000A/messages/01GXYZ0000000000000000000B/reactions/%F0%9F%91%8D"),
+            "/channels/{id}/messages/{id}/reactions/{emoji}"
+        );
+
+        assert_eq!(normalize_route("/invites"), UNKNOWN_ROUTE);
+        assert_eq!(normalize_route("/invites/Testers/extra"), UNKNOWN_ROUTE);
+        assert_eq!(normalize_route("/something/else"), UNKNOWN_ROUTE);
+    }
+
+    #[test]
+    fn renders_prometheus_text() {
+        let metrics = Metrics::new();
+
+        metrics.record_event("Message");
+        metrics.record_event("Message");
+        metrics.record_http_request("GET", "/invites/Testers", 200, Duration::from_millis(20));
+        metrics.record_rate_limit_wait(Duration::from_millis(1500));
+        metrics.record_connection();
+        metrics.record_connection();
+        metrics.record_heartbeat(Duration::from_millis(3));
+
+        let text = metrics.render();
+        let lines = text.lines().collect::<Vec<_>>();
+
+        for line in [
+            "# TYPE harmony_events_total counter",
+            "harmony_events_total{type=\"Message\"} 2",
+            "harmony_http_requests_total{method=\"GET\",route=\"/invites/{code}\",status=\"200\"} 1",
+            "harmony_http_request_duration_seconds_bucket{route=\"/invites/{code}\",le=\"0.01\"} 0",
+            "harmony_http_request_duration_seconds_bucket{route=\"/invites/{code}\",le=\"0.025\"} 1",
+            "harmony_http_request_duration_seconds_bucket{route=\"/invites/{code}\",le=\"+Inf\"} 1",
+            "harmony_http_request_duration_seconds_count{route=\"/invites/{code}\"} 1",
+            "harmony_rate_limit_waits_total 1",
+            "harmony_rate_limit_wait_seconds_total 1.5",
+            "harmony_reconnects_total 1",
+            "harmony_heartbeat_latency_seconds_bucket{le=\"0.005\"} 1",
+            "harmony_heartbeat_latency_seconds_sum 0.003",
+            "harmony_heartbeat_latency_seconds_count 1",
+        ] {
+            assert!(lines.contains(&line), "missing `{line}` in:\n{text}");
+        }
+
+        assert!(!text.contains("Testers"));
+    }
+}
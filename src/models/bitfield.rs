@@ -0,0 +1,145 @@
+//! The bitfields Revolt uses for badges and flags, decoded into enum variants.
+//!
+//! Bits without a matching variant are kept, so a model can be sent back to Revolt
+//! without losing badges or flags this crate doesn't know about yet.
+
+use std::{hash::Hash, marker::PhantomData};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// An enum whose variants are single bits of a bitfield
+pub trait BitFlag: Copy + Eq + Hash + 'static {
+    /// Every variant of the enum
+    const ALL: &'static [Self];
+
+    fn bit(self) -> i32;
+}
+
+/// A set of [`BitFlag`]s, stored as the bitfield it was received as
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BitFlags<T> {
+    bits: i32,
+    flag: PhantomData<T>,
+}
+
+impl<T: BitFlag> BitFlags<T> {
+    pub fn new() -> Self {
+        Self::from_bits(0)
+    }
+
+    /// Decodes a bitfield, keeping the bits of unknown flags
+    pub fn from_bits(bits: i32) -> Self {
+        Self { bits, flag: PhantomData }
+    }
+
+    /// Encodes the flags back into a bitfield, including unknown ones
+    pub fn to_bits(&self) -> i32 {
+        self.bits
+    }
+
+    /// The bits without a matching variant
+    pub fn unknown_bits(&self) -> i32 {
+        T::ALL.iter().fold(self.bits, |bits, flag| bits & !flag.bit())
+    }
+
+    pub fn contains(&self, flag: T) -> bool {
+        self.bits & flag.bit() == flag.bit()
+    }
+
+    pub fn insert(&mut self, flag: T) {
+        self.bits |= flag.bit();
+    }
+
+    pub fn remove(&mut self, flag: T) {
+        self.bits &= !flag.bit();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bits == 0
+    }
+
+    /// The known flags in the set
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        T::ALL.iter()
+            .copied()
+            .filter(|flag| self.contains(*flag))
+    }
+}
+
+impl<T: BitFlag> Default for BitFlags<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: BitFlag> FromIterator<T> for BitFlags<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(flags: I) -> Self {
+        Self::from_bits(flags.into_iter().fold(0, |bits, flag| bits | flag.bit()))
+    }
+}
+
+impl<T: BitFlag> From<T> for BitFlags<T> {
+    fn from(flag: T) -> Self {
+        Self::from_bits(flag.bit())
+    }
+}
+
+impl<T> Serialize for BitFlags<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_i32(self.bits)
+    }
+}
+
+impl<'de, T: BitFlag> Deserialize<'de> for BitFlags<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        i32::deserialize(deserializer).map(Self::from_bits)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::Badge;
+
+    use super::BitFlags;
+
+    #[test]
+    fn decodes_known_bits() {
+        let badges = BitFlags::<Badge>::from_bits(1 | 16);
+
+        assert!(badges.contains(Badge::Developer));
+        assert!(badges.contains(Badge::Founder));
+        assert!(!badges.contains(Badge::Translator));
+        assert_eq!(badges.iter().collect::<Vec<_>>(), [Badge::Developer, Badge::Founder]);
+        assert_eq!(badges.unknown_bits(), 0);
+        assert_eq!(badges.to_bits(), 17);
+    }
+
+    #[test]
+    fn keeps_unknown_bits() {
+        let mut badges = BitFlags::<Badge>::from_bits(1 | 4096 | 1 << 30);
+
+        assert_eq!(badges.iter().collect::<Vec<_>>(), [Badge::Developer]);
+        assert_eq!(badges.unknown_bits(), 4096 | 1 << 30);
+
+        badges.remove(Badge::Developer);
+        badges.insert(Badge::Paw);
+
+        assert_eq!(badges.to_bits(), 128 | 4096 | 1 << 30);
+    }
+
+    #[test]
+    fn round_trips_unknown_bits() {
+        let badges: BitFlags<Badge> = serde_json::from_str("4098").unwrap();
+
+        assert!(badges.contains(Badge::Translator));
+        assert_eq!(serde_json::to_string(&badges).unwrap(), "4098");
+    }
+
+    #[test]
+    fn collects_flags() {
+        let badges = [Badge::Supporter, Badge::EarlyAdopter].into_iter().collect::<BitFlags<_>>();
+
+        assert_eq!(badges.to_bits(), 4 | 256);
+        assert!(BitFlags::<Badge>::default().is_empty());
+    }
+}
@@ -1,17 +1,19 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+use crate::models::id::{ChannelId, MessageId, UserId};
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 pub struct DMChannel {
     #[serde(rename = "_id")]
-    id: String,
+    id: ChannelId,
     active: bool,
-    recipients: Vec<String>,
-    #[serde(default)]
-    last_message_id: Option<String>
+    recipients: Vec<UserId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_message_id: Option<MessageId>
 }
 
 impl DMChannel {
-    pub fn get_id(&self) -> String {
+    pub fn get_id(&self) -> ChannelId {
         self.id.clone()
     }
 
@@ -19,11 +21,11 @@ impl DMChannel {
         self.active
     }
 
-    pub fn get_recipients(&self) -> Vec<String> {
+    pub fn get_recipients(&self) -> Vec<UserId> {
         self.recipients.clone()
     }
 
-    pub fn get_last_message_id(&self) -> Option<String> {
+    pub fn get_last_message_id(&self) -> Option<MessageId> {
         self.last_message_id.clone()
     }
 }
\ No newline at end of file
@@ -1,29 +1,31 @@
 use serde::{Deserialize, Serialize};
 
-use crate::models::file::File;
+use crate::models::id::{ChannelId, MessageId, UserId};
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq, Default)]
+use crate::models::{file::File, is_default};
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 pub struct GroupChannel {
     #[serde(rename = "_id")]
-    id: String,
+    id: ChannelId,
     name: String,
     #[serde(rename = "owner")]
-    owner_id: String,
-    #[serde(default)]
+    owner_id: UserId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     description: Option<String>,
-    recipients: Vec<String>,
-    #[serde(default)]
+    recipients: Vec<UserId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     icon: Option<File>,
-    #[serde(default)]
-    last_message_id: Option<String>,
-    #[serde(default)]
-    permissions: i64,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_message_id: Option<MessageId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    permissions: Option<i64>,
+    #[serde(default, skip_serializing_if = "is_default")]
     nsfw: bool,
 }
 
 impl GroupChannel {
-    pub fn get_id(&self) -> String {
+    pub fn get_id(&self) -> ChannelId {
         self.id.clone()
     }
 
@@ -31,7 +33,7 @@ impl GroupChannel {
         self.name.clone()
     }
 
-    pub fn get_owner_id(&self) -> String {
+    pub fn get_owner_id(&self) -> UserId {
         self.owner_id.clone()
     }
 
@@ -39,7 +41,7 @@ impl GroupChannel {
         self.description.clone()
     }
 
-    pub fn get_recipient_ids(&self) -> Vec<String> {
+    pub fn get_recipient_ids(&self) -> Vec<UserId> {
         self.recipients.clone()
     }
 
@@ -47,11 +49,12 @@ impl GroupChannel {
         self.icon.clone()
     }
 
-    pub fn get_last_message_id(&self) -> Option<String> {
+    pub fn get_last_message_id(&self) -> Option<MessageId> {
         self.last_message_id.clone()
     }
 
-    pub fn get_permissions(&self) -> i64 {
+    /// The permissions given to the group's members, `None` if they have the default permissions
+    pub fn get_permissions(&self) -> Option<i64> {
         self.permissions
     }
 
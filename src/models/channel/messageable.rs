@@ -0,0 +1,315 @@
+use async_trait::async_trait;
+
+use iso8601_timestamp::{Duration, Timestamp};
+
+use crate::{cache::CacheHttp, client::{context::Context, typing::Typing}, http::prelude::{HttpClient, HttpError}, models::{message::{Message, MessageQuery, PartialMessage, MAX_MESSAGE_BATCH}, user::User, id::{ChannelId, MessageId}}};
+
+use super::{Channel, text_channel::TextChannel, dm_channel::DMChannel, group_channel::GroupChannel, notes_channel::NotesChannel};
+
+/// How long after being sent messages can still be bulk deleted
+const BULK_DELETE_MAX_AGE: Duration = Duration::WEEK;
+
+/// Represents a purge that stopped partway through
+#[derive(Debug, thiserror::Error)]
+#[error("Purge failed after deleting {deleted} messages: {source}")]
+pub struct PurgeError {
+    /// How many messages were deleted before the error
+    pub deleted: usize,
+    #[source]
+    pub source: HttpError,
+}
+
+/// Something messages can be sent to, so helpers can target any destination.
+///
+/// The methods take anything implementing [`CacheHttp`], e.g. a `Context` or a `ClientHandle`.
+///
+/// ```no_run
+/// # use harmony::{client::handle::ClientHandle, http::prelude::HttpError, models::channel::messageable::Messageable};
+/// async fn announce<M: Messageable + Sync>(handle: &ClientHandle, destination: &M) -> Result<(), HttpError> {
+///     destination.say(handle, "Restarting in 5 minutes").await?;
+///
+///     Ok(())
+/// }
+/// ```
+#[async_trait]
+pub trait Messageable {
+    /// The id of the channel messages are sent to
+    async fn message_channel_id<C: CacheHttp>(&self, ctx: &C) -> Result<ChannelId, HttpError>;
+
+    async fn send<C: CacheHttp>(&self, ctx: &C, message: PartialMessage) -> Result<Message, HttpError> {
+        let channel_id = self.message_channel_id(ctx).await?;
+
+        ctx.as_ref().send_msg_in_channel(&channel_id, message).await
+    }
+
+    async fn say<C: CacheHttp>(&self, ctx: &C, content: &str) -> Result<Message, HttpError> {
+        let channel_id = self.message_channel_id(ctx).await?;
+
+        ctx.as_ref().say(&channel_id, content).await
+    }
+
+    /// Shows the typing indicator until the returned guard is dropped
+    async fn typing(&self, ctx: &Context) -> Result<Typing, HttpError> {
+        let channel_id = self.message_channel_id(ctx).await?;
+
+        Ok(ctx.typing(&channel_id))
+    }
+
+    async fn fetch_messages<C: CacheHttp>(&self, ctx: &C, query: MessageQuery) -> Result<Vec<Message>, HttpError> {
+        let channel_id = self.message_channel_id(ctx).await?;
+
+        ctx.as_ref().get_messages(&channel_id, &query).await
+    }
+
+    /// Deletes the latest `limit` messages, returning how many were deleted.
+    ///
+    /// Revolt only allows bulk deleting messages less than a week old, so the purge stops
+    /// at the first older message. It needs the `ManageMessages` permission, which makes it
+    /// fail in DMs and saved messages.
+    async fn purge<C: CacheHttp>(&self, ctx: &C, limit: usize) -> Result<usize, PurgeError> {
+        let channel_id = self.message_channel_id(ctx).await
+            .map_err(|source| PurgeError { deleted: 0, source })?;
+
+        let cutoff = Timestamp::now_utc().checked_sub(BULK_DELETE_MAX_AGE);
+
+        purge_messages(&mut ChannelPurge { http: ctx.as_ref(), channel_id }, limit, cutoff).await
+    }
+}
+
+/// Where a purge fetches and deletes messages, split out of [`Messageable::purge`] to test its batching
+#[async_trait]
+trait PurgeTarget: Send {
+    /// The ids of the messages matching `query`, newest first
+    async fn fetch(&mut self, query: &MessageQuery) -> Result<Vec<MessageId>, HttpError>;
+
+    async fn delete(&mut self, ids: &[MessageId]) -> Result<(), HttpError>;
+}
+
+struct ChannelPurge<'a> {
+    http: &'a HttpClient,
+    channel_id: ChannelId,
+}
+
+#[async_trait]
+impl PurgeTarget for ChannelPurge<'_> {
+    async fn fetch(&mut self, query: &MessageQuery) -> Result<Vec<MessageId>, HttpError> {
+        let messages = self.http.get_messages(&self.channel_id, query).await?;
+
+        Ok(messages.into_iter().map(|message| message.id).collect())
+    }
+
+    async fn delete(&mut self, ids: &[MessageId]) -> Result<(), HttpError> {
+        self.http.delete_messages(&self.channel_id, ids).await
+    }
+}
+
+/// Deletes up to `limit` of the target's messages, newest first, in batches.
+/// Stops at the first message sent before `cutoff`, or at any message when there's no cutoff
+async fn purge_messages(target: &mut impl PurgeTarget, limit: usize, cutoff: Option<Timestamp>) -> Result<usize, PurgeError> {
+    let mut deleted = 0;
+    let mut query = MessageQuery::latest(MAX_MESSAGE_BATCH);
+
+    while deleted < limit {
+        let batch_size = (limit - deleted).min(MAX_MESSAGE_BATCH);
+        query.limit = Some(batch_size);
+
+        let ids = target.fetch(&query).await
+            .map_err(|source| PurgeError { deleted, source })?;
+
+        let ids = ids.into_iter()
+            .take_while(|id| id.created_at().zip(cutoff).is_some_and(|(created_at, cutoff)| created_at > cutoff))
+            .collect::<Vec<_>>();
+
+        let Some(oldest) = ids.last() else {
+            break;
+        };
+
+        query.before = Some(oldest.clone());
+
+        target.delete(&ids).await
+            .map_err(|source| PurgeError { deleted, source })?;
+
+        deleted += ids.len();
+
+        // the channel has no more messages, or the rest are too old
+        if ids.len() < batch_size {
+            break;
+        }
+    }
+
+    Ok(deleted)
+}
+
+#[async_trait]
+impl Messageable for TextChannel {
+    async fn message_channel_id<C: CacheHttp>(&self, _ctx: &C) -> Result<ChannelId, HttpError> {
+        Ok(self.get_id())
+    }
+}
+
+#[async_trait]
+impl Messageable for DMChannel {
+    async fn message_channel_id<C: CacheHttp>(&self, _ctx: &C) -> Result<ChannelId, HttpError> {
+        Ok(self.get_id())
+    }
+}
+
+#[async_trait]
+impl Messageable for GroupChannel {
+    async fn message_channel_id<C: CacheHttp>(&self, _ctx: &C) -> Result<ChannelId, HttpError> {
+        Ok(self.get_id())
+    }
+}
+
+#[async_trait]
+impl Messageable for NotesChannel {
+    async fn message_channel_id<C: CacheHttp>(&self, _ctx: &C) -> Result<ChannelId, HttpError> {
+        Ok(self.get_id())
+    }
+}
+
+/// Voice channels don't hold messages, so they fail with [`HttpError::NotMessageable`]
+#[async_trait]
+impl Messageable for Channel {
+    async fn message_channel_id<C: CacheHttp>(&self, _ctx: &C) -> Result<ChannelId, HttpError> {
+        match self {
+            Self::VoiceChannel(channel) => Err(HttpError::NotMessageable(channel.get_id())),
+            _ => Ok(self.get_id()),
+        }
+    }
+}
+
+/// Messages are sent to the DM channel with the user, which is opened when it isn't cached yet
+#[async_trait]
+impl Messageable for User {
+    async fn message_channel_id<C: CacheHttp>(&self, ctx: &C) -> Result<ChannelId, HttpError> {
+        let cache = ctx.shared_cache();
+
+        if let Some(cache) = cache {
+            if let Some(channel) = cache.read().await.get_dm_channel(&self.id) {
+                return Ok(channel.get_id());
+            }
+        }
+
+        let channel = ctx.as_ref().open_dm(&self.id).await?;
+        let channel_id = channel.get_id();
+
+        if let Some(cache) = cache {
+            cache.write().await.channels.insert(channel_id.clone(), channel);
+        }
+
+        Ok(channel_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use iso8601_timestamp::{Duration, Timestamp};
+
+    use crate::{http::prelude::HttpError, models::{id::MessageId, message::{MessageQuery, MAX_MESSAGE_BATCH}}};
+
+    use super::{purge_messages, PurgeTarget};
+
+    const CROCKFORD_ALPHABET: &[u8] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+    /// A message id created `millis` after the epoch
+    fn id_at(millis: u64) -> MessageId {
+        let time = (0..10).rev()
+            .map(|i| CROCKFORD_ALPHABET[(millis >> (5 * i)) as usize & 31] as char)
+            .collect::<String>();
+
+        MessageId::new(&format!("{time}{millis:016}")).unwrap()
+    }
+
+    fn cutoff(millis: i64) -> Option<Timestamp> {
+        Timestamp::UNIX_EPOCH.checked_add(Duration::milliseconds(millis))
+    }
+
+    /// A channel holding messages sent at the given times, newest first
+    #[derive(Default)]
+    struct FakeChannel {
+        messages: Vec<MessageId>,
+        batches: Vec<Vec<MessageId>>,
+        fail_delete_at: Option<usize>,
+    }
+
+    impl FakeChannel {
+        fn new(times: impl IntoIterator<Item = u64>) -> Self {
+            let mut messages = times.into_iter().map(id_at).collect::<Vec<_>>();
+            messages.sort_by(|a, b| b.cmp(a));
+
+            Self { messages, ..Default::default() }
+        }
+    }
+
+    #[async_trait]
+    impl PurgeTarget for FakeChannel {
+        async fn fetch(&mut self, query: &MessageQuery) -> Result<Vec<MessageId>, HttpError> {
+            Ok(self.messages.iter()
+                .filter(|id| query.before.as_ref().is_none_or(|before| *id < before))
+                .take(query.limit.unwrap_or(MAX_MESSAGE_BATCH))
+                .cloned()
+                .collect())
+        }
+
+        async fn delete(&mut self, ids: &[MessageId]) -> Result<(), HttpError> {
+            if self.fail_delete_at == Some(self.batches.len()) {
+                return Err(HttpError::UserSessionRequired);
+            }
+
+            self.messages.retain(|id| !ids.contains(id));
+            self.batches.push(ids.to_vec());
+
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn stops_at_the_cutoff() {
+        let mut channel = FakeChannel::new([1000, 2000, 3000, 4000, 5000]);
+
+        assert_eq!(purge_messages(&mut channel, 10, cutoff(2500)).await.unwrap(), 3);
+        assert_eq!(channel.messages, [id_at(2000), id_at(1000)]);
+
+        assert_eq!(purge_messages(&mut channel, 10, cutoff(2500)).await.unwrap(), 0);
+        assert_eq!(channel.batches.len(), 1);
+
+        // messages without a known age are never deleted
+        assert_eq!(purge_messages(&mut channel, 10, None).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn deletes_in_batches() {
+        let total = MAX_MESSAGE_BATCH as u64 * 2 + 50;
+        let mut channel = FakeChannel::new(1..=total);
+
+        let deleted = purge_messages(&mut channel, 230, cutoff(0)).await.unwrap();
+
+        assert_eq!(deleted, 230);
+        assert_eq!(channel.batches.iter().map(Vec::len).collect::<Vec<_>>(), [100, 100, 30]);
+        assert_eq!(channel.messages.len(), 20);
+        assert_eq!(channel.messages.first(), Some(&id_at(20)));
+    }
+
+    #[tokio::test]
+    async fn stops_after_a_partial_batch() {
+        let mut channel = FakeChannel::new(1..=120);
+
+        assert_eq!(purge_messages(&mut channel, 500, cutoff(0)).await.unwrap(), 120);
+        assert_eq!(channel.batches.iter().map(Vec::len).collect::<Vec<_>>(), [100, 20]);
+        assert!(channel.messages.is_empty());
+    }
+
+    #[tokio::test]
+    async fn reports_deleted_messages_when_failing_midway() {
+        let mut channel = FakeChannel::new(1..=250);
+        channel.fail_delete_at = Some(2);
+
+        let err = purge_messages(&mut channel, 250, cutoff(0)).await.unwrap_err();
+
+        assert_eq!(err.deleted, 200);
+        assert!(matches!(err.source, HttpError::UserSessionRequired));
+        assert_eq!(channel.messages.len(), 50);
+    }
+}
@@ -1,10 +1,10 @@
 use serde::{Deserialize, Serialize};
 
-use crate::{client::context::Context, http::prelude::HttpError};
+use crate::{client::{context::Context, typing::Typing}, http::prelude::{HttpClient, HttpError}};
 
 use self::{text_channel::TextChannel, notes_channel::NotesChannel, dm_channel::DMChannel, group_channel::GroupChannel, voice_channel::VoiceChannel};
 
-use super::message::{PartialMessage, Message};
+use super::{message::{PartialMessage, Message}, invite::Invite, id::{ChannelId, ServerId}};
 
 pub mod partial_channel;
 pub mod text_channel;
@@ -12,6 +12,37 @@ pub mod notes_channel;
 pub mod dm_channel;
 pub mod group_channel;
 pub mod voice_channel;
+pub mod messageable;
+
+/// The kinds of channel that can be created in a server
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, Eq, PartialEq, Default)]
+pub enum ServerChannelType {
+    #[default]
+    Text,
+    Voice,
+}
+
+/// A channel to create in a server
+#[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq, Default)]
+pub struct CreateChannel {
+    #[serde(rename = "type")]
+    pub kind: ServerChannelType,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nsfw: Option<bool>,
+}
+
+impl CreateChannel {
+    pub fn new(kind: ServerChannelType, name: &str) -> Self {
+        Self {
+            kind,
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+}
 
 #[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq)]
 #[serde(tag = "channel_type")]
@@ -76,7 +107,16 @@ impl Channel {
         }
     }
 
-    pub fn get_id(&self) -> String {
+    /// The id of the server the channel belongs to, if it is a server channel
+    pub fn get_server_id(&self) -> Option<ServerId> {
+        match &self {
+            Channel::TextChannel(text) => Some(text.get_server_id()),
+            Channel::VoiceChannel(voice) => Some(voice.get_server_id()),
+            _ => None,
+        }
+    }
+
+    pub fn get_id(&self) -> ChannelId {
         match &self {
             Channel::SavedMessages(notes) => notes.get_id(),
             Channel::DirectMessage(dm) => dm.get_id(),
@@ -95,46 +135,36 @@ impl Channel {
     pub async fn send_message(&self, ctx: &mut Context, message: PartialMessage) -> Result<Message, HttpError> {
         ctx.http.send_msg_in_channel(&self.get_id(), message).await
     }
+
+    /// Shows the typing indicator in this channel until the returned guard is dropped
+    pub fn start_typing(&self, ctx: &Context) -> Typing {
+        ctx.typing(&self.get_id())
+    }
+
+    /// Creates an invite to this channel, which must be a server channel or group
+    pub async fn create_invite(&self, ctx: &impl AsRef<HttpClient>) -> Result<Invite, HttpError> {
+        ctx.as_ref().create_invite(&self.get_id()).await
+    }
 }
 
 impl Channel {
     pub fn is_notes(&self) -> bool {
-        if let Self::SavedMessages(_) = self {
-            true
-        } else {
-            false
-        }
+        matches!(self, Self::SavedMessages(_))
     }
 
     pub fn is_dm(&self) -> bool {
-        if let Self::DirectMessage(_) = self {
-            true
-        } else {
-            false
-        }
+        matches!(self, Self::DirectMessage(_))
     }
 
     pub fn is_group(&self) -> bool {
-        if let Self::Group(_) = self {
-            true
-        } else {
-            false
-        }
+        matches!(self, Self::Group(_))
     }
 
     pub fn is_text(&self) -> bool {
-        if let Self::TextChannel(_) = self {
-            true
-        } else {
-            false
-        }
+        matches!(self, Self::TextChannel(_))
     }
 
     pub fn is_voice(&self) -> bool {
-        if let Self::VoiceChannel(_) = self {
-            true
-        } else {
-            false
-        }
+        matches!(self, Self::VoiceChannel(_))
     }
 }
\ No newline at end of file
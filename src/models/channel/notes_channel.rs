@@ -1,18 +1,20 @@
 use serde::{Serialize, Deserialize};
 
+use crate::models::id::{ChannelId, UserId};
+
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct NotesChannel {
     #[serde(rename = "_id")]
-    id: String,
-    user: String,
+    id: ChannelId,
+    user: UserId,
 }
 
 impl NotesChannel {
-    pub fn get_id(&self) -> String {
+    pub fn get_id(&self) -> ChannelId {
         self.id.clone()
     }
 
-    pub fn get_user_id(&self) -> String {
+    pub fn get_user_id(&self) -> UserId {
         self.user.clone()
     }
 }
\ No newline at end of file
@@ -1,30 +1,34 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-use crate::models::{file::File, server::PermissionOverride};
+use crate::models::id::{ChannelId, MessageId, RoleId, ServerId};
+
+use crate::models::{file::File, server::PermissionOverride, is_default};
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Eq, Clone)]
 pub struct TextChannel {
     #[serde(rename = "server")]
-    server_id: String,
+    server_id: ServerId,
     #[serde(rename = "_id")]
-    id: String,
+    id: ChannelId,
     name: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     description: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     icon: Option<File>,
-    #[serde(default)]
-    last_message_id: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_message_id: Option<MessageId>,
+    #[serde(default, skip_serializing_if = "is_default")]
     default_permissions: PermissionOverride,
-    #[serde(default)]
-    role_permissions: PermissionOverride,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    role_permissions: HashMap<RoleId, PermissionOverride>,
+    #[serde(default, skip_serializing_if = "is_default")]
     nsfw: bool,
 }
 
 impl TextChannel {
-    pub fn get_id(&self) -> String {
+    pub fn get_id(&self) -> ChannelId {
         self.id.clone()
     }
 
@@ -32,7 +36,7 @@ impl TextChannel {
         self.name.clone()
     }
     
-    pub fn get_server_id(&self) -> String {
+    pub fn get_server_id(&self) -> ServerId {
         self.server_id.clone()
     }
 
@@ -44,11 +48,12 @@ impl TextChannel {
         self.icon.clone()
     }
 
-    pub fn get_last_message_id(&self) -> Option<String> {
+    pub fn get_last_message_id(&self) -> Option<MessageId> {
         self.last_message_id.clone()
     }
 
-    pub fn get_role_permissions(&self) -> PermissionOverride {
+    /// The permission overrides for each role, keyed by role id
+    pub fn get_role_permissions(&self) -> HashMap<RoleId, PermissionOverride> {
         self.role_permissions.clone()
     }
 
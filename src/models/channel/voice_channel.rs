@@ -1,28 +1,32 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 
-use crate::models::{file::File, server::PermissionOverride};
+use crate::models::id::{ChannelId, RoleId, ServerId};
+
+use crate::models::{file::File, server::PermissionOverride, is_default};
 
-#[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq, Default)]
+#[derive(Debug, Deserialize, Serialize, Clone, Eq, PartialEq)]
 pub struct VoiceChannel {
     #[serde(rename = "server")]
-    server_id: String,
+    server_id: ServerId,
     #[serde(rename = "_id")]
-    id: String,
+    id: ChannelId,
     name: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     description: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     icon: Option<File>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     default_permissions: PermissionOverride,
-    #[serde(default)]
-    role_permissions: PermissionOverride,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    role_permissions: HashMap<RoleId, PermissionOverride>,
+    #[serde(default, skip_serializing_if = "is_default")]
     nsfw: bool,
 }
 
 impl VoiceChannel {
-    pub fn get_id(&self) -> String {
+    pub fn get_id(&self) -> ChannelId {
         self.id.clone()
     }
 
@@ -30,7 +34,7 @@ impl VoiceChannel {
         self.name.clone()
     }
 
-    pub fn get_server_id(&self) -> String {
+    pub fn get_server_id(&self) -> ServerId {
         self.server_id.clone()
     }
 
@@ -46,7 +50,8 @@ impl VoiceChannel {
         self.default_permissions.clone()
     }
 
-    pub fn get_role_permissions(&self) -> PermissionOverride {
+    /// The permission overrides for each role, keyed by role id
+    pub fn get_role_permissions(&self) -> HashMap<RoleId, PermissionOverride> {
         self.role_permissions.clone()
     }
 
@@ -0,0 +1,285 @@
+use std::ops::Range;
+
+use crate::cache::Cache;
+
+use super::{id::{ChannelId, EmojiId, RoleId, ServerId, UserId}, invite::is_invite_code};
+
+/// Hosts whose links are server invites, along with the path before the invite code
+const INVITE_PREFIXES: [&str; 4] = [
+    "rvlt.gg/",
+    "app.revolt.chat/invite/",
+    "revolt.chat/invite/",
+    "local.revolt.chat/invite/",
+];
+
+/// Characters trimmed from the end of links, as they usually end the sentence rather than the link
+const TRAILING_PUNCTUATION: [char; 9] = ['.', ',', '!', '?', ';', ':', '\'', '"', ']'];
+
+/// Something referenced in a message's content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntityKind {
+    /// `<@id>`
+    UserMention(UserId),
+    /// `<#id>`
+    ChannelMention(ChannelId),
+    /// `<%id>`
+    RoleMention(RoleId),
+    /// A custom emoji, `:id:`
+    Emoji(EmojiId),
+    Url(String),
+    /// A link to a server invite, with its code
+    Invite(String),
+}
+
+/// An entity along with the byte range it spans in the content
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContentEntity {
+    pub kind: EntityKind,
+    pub span: Range<usize>,
+}
+
+/// Finds the mentions, custom emoji, links and invites in a message's content,
+/// in the order they appear. Anything inside inline code or code blocks is skipped
+pub fn parse_content(content: &str) -> Vec<ContentEntity> {
+    let mut entities = Vec::new();
+    let mut i = 0;
+
+    while i < content.len() {
+        let rest = &content[i..];
+
+        let parsed = if rest.starts_with('`') {
+            // skip over the code, or just the backticks if they aren't closed
+            let fence = rest.len() - rest.trim_start_matches('`').len();
+
+            i += rest[fence..].find(&rest[..fence])
+                .map_or(fence, |end| fence + end + fence);
+
+            continue;
+        } else if rest.starts_with('<') {
+            parse_mention_at(rest)
+        } else if rest.starts_with(':') {
+            parse_emoji_at(rest)
+        } else if rest.starts_with("http://") || rest.starts_with("https://") {
+            parse_url_at(rest)
+        } else {
+            None
+        };
+
+        match parsed {
+            Some((kind, len)) => {
+                entities.push(ContentEntity { kind, span: i..i + len });
+                i += len;
+            },
+            None => i += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+
+    entities
+}
+
+/// Replaces mentions with the names of what they mention, resolved against the cache.
+/// Nicknames from `server_id` are used for users, and mentions that can't be resolved are left as is
+pub fn clean_content(content: &str, cache: &Cache, server_id: Option<&ServerId>) -> String {
+    let server = server_id.and_then(|server_id| cache.servers.get(server_id));
+
+    let mut clean = String::with_capacity(content.len());
+    let mut last = 0;
+
+    for entity in parse_content(content) {
+        let name = match &entity.kind {
+            EntityKind::UserMention(id) => cache.get_display_name(server_id, id)
+                .map(|name| format!("@{name}")),
+            EntityKind::ChannelMention(id) => cache.channels.get(id)
+                .and_then(|channel| channel.get_name())
+                .map(|name| format!("#{name}")),
+            EntityKind::RoleMention(id) => server
+                .and_then(|server| server.roles.get(id))
+                .map(|role| format!("@{}", role.name)),
+            _ => None,
+        };
+
+        if let Some(name) = name {
+            clean.push_str(&content[last..entity.span.start]);
+            clean.push_str(&name);
+            last = entity.span.end;
+        }
+    }
+
+    clean.push_str(&content[last..]);
+
+    clean
+}
+
+/// Gets the invite code from an invite link, e.g. `https://rvlt.gg/Testers`
+pub fn parse_invite_code(url: &str) -> Option<String> {
+    let url = url.strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .unwrap_or(url);
+
+    let path = INVITE_PREFIXES.iter()
+        .find_map(|prefix| url.strip_prefix(prefix))?;
+
+    let code = path.split(['/', '?', '#']).next()?;
+
+    is_invite_code(code).then(|| code.to_string())
+}
+
+/// Strips the mention syntax from `arg`, e.g. `<@01ABC>` with the prefix `<@` becomes `01ABC`
+pub fn parse_mention<'a>(arg: &'a str, prefix: &str) -> Option<&'a str> {
+    arg.strip_prefix(prefix)?
+        .strip_suffix('>')
+        .filter(|id| !id.is_empty())
+}
+
+fn is_id_char(c: char) -> bool {
+    c.is_ascii_alphanumeric()
+}
+
+fn parse_mention_at(rest: &str) -> Option<(EntityKind, usize)> {
+    let end = rest.find('>')?;
+    let mention = &rest[..=end];
+
+    let id = ["<@", "<#", "<%"].into_iter()
+        .find_map(|prefix| parse_mention(mention, prefix))?;
+
+    // anything that isn't an id is left as text
+    let kind = match &mention[..2] {
+        "<@" => EntityKind::UserMention(UserId::new(id).ok()?),
+        "<#" => EntityKind::ChannelMention(ChannelId::new(id).ok()?),
+        _ => EntityKind::RoleMention(RoleId::new(id).ok()?),
+    };
+
+    Some((kind, mention.len()))
+}
+
+fn parse_emoji_at(rest: &str) -> Option<(EntityKind, usize)> {
+    let id = rest[1..].split(':').next()?;
+
+    // custom emoji are referenced by their 26 character id, anything else is a unicode shortcode
+    if id.len() != 26 || !id.chars().all(is_id_char) || !rest[1 + id.len()..].starts_with(':') {
+        return None;
+    }
+
+    Some((EntityKind::Emoji(EmojiId::new(id).ok()?), id.len() + 2))
+}
+
+fn parse_url_at(rest: &str) -> Option<(EntityKind, usize)> {
+    let end = rest.find(|c: char| c.is_whitespace() || c == '<' || c == '>')
+        .unwrap_or(rest.len());
+
+    // closing parentheses are only trimmed when unbalanced, so wikipedia's `Rust_(language)` keeps its own
+    let mut url = rest[..end].trim_end_matches(TRAILING_PUNCTUATION);
+
+    while url.ends_with(')') && url.matches(')').count() > url.matches('(').count() {
+        url = url[..url.len() - 1].trim_end_matches(TRAILING_PUNCTUATION);
+    }
+
+    if url.ends_with("://") {
+        return None;
+    }
+
+    let kind = match parse_invite_code(url) {
+        Some(code) => EntityKind::Invite(code),
+        None => EntityKind::Url(url.to_string()),
+    };
+
+    Some((kind, url.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{cache::Cache, models::{channel::Channel, server::Server, user::User}};
+
+    use super::*;
+
+    const USER_ID: &str = "01FD58YK5W7QRV5H3D64KTQYX3";
+    const CHANNEL_ID: &str = "01H2X8V3YJ5QK2W7D9R6T4B1CA";
+    const ROLE_ID: &str = "01H2X8W9J3K5M7N9P1Q3R5S7T9";
+
+    fn kinds(content: &str) -> Vec<EntityKind> {
+        parse_content(content).into_iter().map(|entity| entity.kind).collect()
+    }
+
+    fn url(url: &str) -> EntityKind {
+        EntityKind::Url(url.to_string())
+    }
+
+    #[test]
+    fn parses_mentions() {
+        assert_eq!(parse_mention("<@01ABC>", "<@"), Some("01ABC"));
+        assert_eq!(parse_mention("<@>", "<@"), None);
+        assert_eq!(parse_mention("<#01ABC>", "<@"), None);
+        assert_eq!(parse_mention("<@01ABC", "<@"), None);
+    }
+
+    #[test]
+    fn finds_entities_in_order() {
+        let content = format!("hi <@{USER_ID}>, see <#{CHANNEL_ID}> and <%{ROLE_ID}> :{USER_ID}: https://rvlt.gg/Testers");
+        let entities = parse_content(&content);
+
+        assert_eq!(entities.iter().map(|entity| entity.kind.clone()).collect::<Vec<_>>(), [
+            EntityKind::UserMention(USER_ID.parse().unwrap()),
+            EntityKind::ChannelMention(CHANNEL_ID.parse().unwrap()),
+            EntityKind::RoleMention(ROLE_ID.parse().unwrap()),
+            EntityKind::Emoji(USER_ID.parse().unwrap()),
+            EntityKind::Invite("Testers".to_string()),
+        ]);
+        assert_eq!(&content[entities[0].span.clone()], format!("<@{USER_ID}>"));
+    }
+
+    #[test]
+    fn skips_code() {
+        assert!(kinds(&format!("`<@{USER_ID}>`")).is_empty());
+        assert!(kinds(&format!("```\n<@{USER_ID}> https://revolt.chat\n```")).is_empty());
+        assert_eq!(kinds(&format!("`unclosed <@{USER_ID}>")), [EntityKind::UserMention(USER_ID.parse().unwrap())]);
+    }
+
+    #[test]
+    fn leaves_invalid_mentions_as_text() {
+        assert!(kinds("<@someone> <#> :smile:").is_empty());
+    }
+
+    #[test]
+    fn trims_punctuation_after_links() {
+        assert_eq!(kinds("see https://revolt.chat."), [url("https://revolt.chat")]);
+        assert_eq!(kinds("(https://revolt.chat/about)"), [url("https://revolt.chat/about")]);
+        assert_eq!(kinds("https://en.wikipedia.org/wiki/Rust_(language)"), [url("https://en.wikipedia.org/wiki/Rust_(language)")]);
+        assert_eq!(kinds("(https://en.wikipedia.org/wiki/Rust_(language))!"), [url("https://en.wikipedia.org/wiki/Rust_(language)")]);
+        assert!(kinds("https://").is_empty());
+    }
+
+    #[test]
+    fn parses_invite_codes() {
+        assert_eq!(parse_invite_code("https://rvlt.gg/Testers"), Some("Testers".to_string()));
+        assert_eq!(parse_invite_code("app.revolt.chat/invite/Testers?ref=1"), Some("Testers".to_string()));
+        assert_eq!(parse_invite_code("http://revolt.chat/invite/abc123/"), Some("abc123".to_string()));
+        assert_eq!(parse_invite_code("https://rvlt.gg/"), None);
+        assert_eq!(parse_invite_code("https://rvlt.gg/not-a-code"), None);
+        assert_eq!(parse_invite_code("https://example.com/invite/Testers"), None);
+    }
+
+    #[test]
+    fn cleans_mentions() {
+        let mut cache = Cache::new(16, 0);
+
+        let user: User = serde_json::from_str(include_str!("../../tests/fixtures/user.json")).unwrap();
+        let channel: Channel = serde_json::from_str(include_str!("../../tests/fixtures/text_channel.json")).unwrap();
+        let server: Server = serde_json::from_str(include_str!("../../tests/fixtures/server.json")).unwrap();
+        let server_id = server.id.clone();
+
+        cache.users.insert(user.id.clone(), user);
+        cache.channels.insert(channel.get_id(), channel);
+        cache.servers.insert(server.id.clone(), server);
+
+        let content = format!("<@{USER_ID}> in <#{CHANNEL_ID}> is <%{ROLE_ID}>, `<@{USER_ID}>` <@01H2X8W9J3K5M7N9P1Q3R5S7TA>");
+
+        assert_eq!(
+            clean_content(&content, &cache, Some(&server_id)),
+            format!("@Insert in #general is @Moderator, `<@{USER_ID}>` <@01H2X8W9J3K5M7N9P1Q3R5S7TA>"),
+        );
+        assert_eq!(
+            clean_content(&format!("<%{ROLE_ID}>"), &cache, None),
+            format!("<%{ROLE_ID}>"),
+        );
+    }
+}
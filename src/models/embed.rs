@@ -1,31 +1,164 @@
 use serde::{Deserialize, Serialize};
 
-use super::file::File;
+use crate::client::colors::{Colour, ColourError};
+
+use super::{file::File, id::FileId};
+
+/// The maximum number of characters in an embed's title
+pub const MAX_EMBED_TITLE_LENGTH: usize = 100;
+/// The maximum number of characters in an embed's description
+pub const MAX_EMBED_DESCRIPTION_LENGTH: usize = 2000;
+/// The maximum number of characters in an embed's url
+pub const MAX_EMBED_URL_LENGTH: usize = 256;
+/// The maximum number of characters in an embed's icon url and media id
+pub const MAX_EMBED_FIELD_LENGTH: usize = 128;
+
+/// An embed that can be sent with a message, usually built with [`EmbedBuilder`]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct SendableEmbed {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub icon_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The id of a file uploaded to Autumn
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub media: Option<FileId>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub colour: Option<Colour>,
+}
+
+impl SendableEmbed {
+    /// Checks the embed against Revolt's limits
+    pub fn validate(&self) -> Result<(), EmbedValidationError> {
+        let fields = [
+            ("icon_url", self.icon_url.as_deref(), MAX_EMBED_FIELD_LENGTH),
+            ("url", self.url.as_deref(), MAX_EMBED_URL_LENGTH),
+            ("title", self.title.as_deref(), MAX_EMBED_TITLE_LENGTH),
+            ("description", self.description.as_deref(), MAX_EMBED_DESCRIPTION_LENGTH),
+            ("media", self.media.as_ref().map(FileId::as_str), MAX_EMBED_FIELD_LENGTH),
+        ];
+
+        for (field, value, max) in fields {
+            if let Some(value) = value {
+                if !(1..=max).contains(&value.chars().count()) {
+                    return Err(EmbedValidationError::InvalidLength { field, max });
+                }
+            }
+        }
+
+        if let Some(colour) = &self.colour {
+            colour.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Represents an embed that would be rejected by Revolt
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum EmbedValidationError {
+    #[error("The embed's {field} must be between 1 and {max} characters")]
+    InvalidLength { field: &'static str, max: usize },
+    #[error("Invalid embed colour: {0}")]
+    InvalidColour(#[from] ColourError),
+}
+
+/// Builds a [`SendableEmbed`], checking Revolt's limits when it's built.
+///
+/// ```no_run
+/// # use harmony::{client::colors::Rgb, models::embed::{EmbedBuilder, EmbedValidationError}};
+/// # fn main() -> Result<(), EmbedValidationError> {
+/// let embed = EmbedBuilder::new()
+///     .title("Status")
+///     .description("All systems operational")
+///     .colour(Rgb::new(32, 200, 120))
+///     .build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct EmbedBuilder {
+    embed: SendableEmbed,
+}
+
+impl EmbedBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn title(mut self, title: &str) -> Self {
+        self.embed.title = Some(title.to_string());
+
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.embed.description = Some(description.to_string());
+
+        self
+    }
+
+    pub fn url(mut self, url: &str) -> Self {
+        self.embed.url = Some(url.to_string());
+
+        self
+    }
+
+    pub fn icon_url(mut self, icon_url: &str) -> Self {
+        self.embed.icon_url = Some(icon_url.to_string());
+
+        self
+    }
+
+    /// Shows a file that was uploaded to Autumn, by its id
+    pub fn media(mut self, file_id: &FileId) -> Self {
+        self.embed.media = Some(file_id.clone());
+
+        self
+    }
+
+    /// Sets the colour of the embed, e.g. an `Rgb` or `LinearGradient` from `client::colors`
+    pub fn colour<C: Into<Colour>>(mut self, colour: C) -> Self {
+        self.embed.colour = Some(colour.into());
+
+        self
+    }
+
+    pub fn build(self) -> Result<SendableEmbed, EmbedValidationError> {
+        self.embed.validate()?;
+
+        Ok(self.embed)
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(tag = "type")]
 pub enum Embed {
     Website {
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         url: Option<String>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         original_url: Option<String>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         special: Option<RemoteContent>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         title: Option<String>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         description: Option<String>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         image: Option<Image>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         video: Option<Video>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         site_name: Option<String>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         icon_url: Option<String>,
-        #[serde(default)]
-        colour: Option<String>
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        colour: Option<Colour>
     },
     Image {
         url: String,
@@ -39,18 +172,18 @@ pub enum Embed {
         height: usize
     },
     Text {
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         icon_url: Option<String>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         url: Option<String>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         title: Option<String>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         description: Option<String>,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         media: Option<File>,
-        #[serde(default)]
-        colour: Option<String>
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        colour: Option<Colour>
     },
     None
 }
@@ -84,7 +217,7 @@ pub enum RemoteContent {
     GIF,
     YouTube {
         id: String,
-        #[serde(default)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
         timestamp: Option<String>
     },
     Lightspeed {
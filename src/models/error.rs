@@ -1,65 +1,132 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 use super::{Permission, user::UserPermission};
 
 /// Represents an error when performing an action with Revolt's API
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, thiserror::Error)]
 #[serde(tag = "type")]
 pub enum RevoltError {
     #[serde(rename = "LabelMe")]
+    #[error("Uncategorized error")]
     Default,
+    #[error("This account has already completed onboarding")]
     AlreadyOnboarded,
+    #[error("This username is already taken")]
     UsernameTaken,
+    #[error("This username is invalid")]
     InvalidUsername,
+    #[error("The user does not exist")]
     UnknownUser,
+    #[error("Already friends with this user")]
     AlreadyFriends,
+    #[error("A friend request has already been sent to this user")]
     AlreadySentRequest,
+    #[error("You have blocked this user")]
     Blocked,
+    #[error("This user has blocked you")]
     BlockedByOther,
+    #[error("Not friends with this user")]
     NotFriends,
+    #[error("The channel does not exist")]
     UnknownChannel,
+    #[error("The attachment does not exist")]
     UnknownAttachment,
+    #[error("The message does not exist")]
     UnknownMessage,
+    #[error("This message cannot be edited")]
     CannotEditMessage,
+    #[error("Cannot join this call")]
     CannotJoinCall,
+    #[error("Too many attachments, the maximum is {max}")]
     TooManyAttachments { max: usize },
+    #[error("Too many replies, the maximum is {max}")]
     TooManyReplies { max: usize },
+    #[error("Too many channels, the maximum is {max}")]
     TooManyChannels { max: usize },
+    #[error("Too many embeds, the maximum is {max}")]
     TooManyEmbeds { max: usize },
+    #[error("Cannot send an empty message")]
     EmptyMessage,
+    #[error("The payload is too large")]
     PayloadTooLarge,
+    #[error("Cannot remove yourself")]
     CannotRemoveYourself,
+    #[error("The group is too large, the maximum is {max}")]
     GroupTooLarge { max: usize },
+    #[error("Already in this group")]
     AlreadyInGroup,
+    #[error("Not in this group")]
     NotInGroup,
+    #[error("The server does not exist")]
     UnknownServer,
+    #[error("The role is invalid")]
     InvalidRole,
+    #[error("Banned from this server")]
     Banned,
+    #[error("In too many servers, the maximum is {max}")]
     TooManyServers { max: usize },
+    #[error("Too many emoji, the maximum is {max}")]
     TooManyEmoji { max: usize },
+    #[error("Too many roles, the maximum is {max}")]
     TooManyRoles { max: usize },
+    #[error("Reached the maximum number of bots")]
     ReachedMaximumBots,
+    #[error("This action cannot be performed by a bot")]
     IsBot,
+    #[error("This bot is private")]
     BotIsPrivate,
+    #[error("Cannot report yourself")]
     CannotReportYourself,
+    #[error("Missing the {permission:?} permission")]
     MissingPermission { permission: Permission },
+    #[error("Missing the {permission:?} user permission")]
     MissingUserPermission { permission: UserPermission },
+    #[error("This session is not elevated")]
     NotElevated,
+    #[error("This account is not privileged")]
     NotPrivileged,
+    #[error("Cannot give permissions you do not have")]
     CannotGiveMissingPermissions,
+    #[error("Not the owner")]
     NotOwner,
+    #[error("Database error during {operation} with {with}")]
     DatabaseError {
         operation: String,
         with: String
     },
+    #[error("The server has encountered an error")]
     InternalError,
+    #[error("This operation is invalid")]
     InvalidOperation,
+    #[error("The credentials are incorrect")]
     InvalidCredentials,
+    #[error("A property is invalid")]
     InvalidProperty,
+    #[error("The session is invalid")]
     InvalidSession,
+    #[error("A message with this nonce has already been sent")]
     DuplicateNonce,
+    #[error("Voice is unavailable")]
     VosoUnavailable,
+    #[error("The resource does not exist")]
     NotFound,
+    #[error("This action has no effect")]
     NoEffect,
+    #[error("The request failed validation")]
     FailedValidation,
-}
\ No newline at end of file
+}
+
+impl RevoltError {
+    /// Checks if the error means the requested resource doesn't exist
+    pub fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            Self::NotFound
+                | Self::UnknownUser
+                | Self::UnknownChannel
+                | Self::UnknownAttachment
+                | Self::UnknownMessage
+                | Self::UnknownServer
+        )
+    }
+}
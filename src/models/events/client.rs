@@ -1,5 +1,7 @@
 use serde::Serialize;
 
+use crate::models::id::ChannelId;
+
 #[derive(Debug, Serialize, Clone)]
 #[serde(tag = "type")]
 pub enum ClientEvent {
@@ -8,11 +10,11 @@ pub enum ClientEvent {
     },
     BeginTyping {
         #[serde(rename = "channel")]
-        channel_id: String
+        channel_id: ChannelId
     },
     EndTyping {
         #[serde(rename = "channel")]
-        channel_id: String
+        channel_id: ChannelId
     },
     Ping {
         data: i32
@@ -1,9 +1,11 @@
 use iso8601_timestamp::Timestamp;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-use crate::models::{message::{Message, PartialMessage}, channel::{Channel, partial_channel::PartialChannel}, server::{Server, PartialServer}, Emoji, user::{RelationshipStatus, User}, embed::Embed, file::File};
+use crate::models::{message::{Message, MessageUpdateData}, channel::{Channel, partial_channel::PartialChannel}, server::{Server, PartialServer}, Emoji, user::{RelationshipStatus, User, PartialStatus, UserProfile, Presence}, embed::Embed, file::File, bitfield::BitFlags, id::{ChannelId, EmojiId, MessageId, RoleId, ServerId, UserId}, Badge, Flag};
 
-#[derive(Debug, Deserialize, thiserror::Error, Default, Clone)]
+pub use crate::models::member::{Member, MemberId};
+
+#[derive(Debug, Serialize, Deserialize, thiserror::Error, Default, Clone)]
 pub enum ServerError {
     #[default]
     #[error("Uncategorized error")]
@@ -18,7 +20,7 @@ pub enum ServerError {
     AlreadyAuthenticated
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "type")]
 pub enum ServerEvent {
     Error { error: ServerError },
@@ -35,7 +37,7 @@ pub enum ServerEvent {
     MessageRemoveReaction(RemoveReactionEvent),
     ChannelCreate(Channel),
     ChannelUpdate(ChannelUpdateEvent),
-    ChannelDelete { id: String },
+    ChannelDelete { id: ChannelId },
     ChannelGroupJoin(Event),
     ChannelGroupLeave(Event),
     ChannelStartTyping(Event),
@@ -43,17 +45,17 @@ pub enum ServerEvent {
     ChannelAck(ChannelMessageAcknowledgeEvent),
     ServerCreate(Server),
     ServerUpdate(ServerUpdateEvent),
-    ServerDelete { id: String },
+    ServerDelete { id: ServerId },
     ServerMemberUpdate(ServerMemberUpdate),
-    ServerMemberJoin(Event),
-    ServerMemberLeave(Event),
+    ServerMemberJoin(MemberJoinEvent),
+    ServerMemberLeave(MemberLeaveEvent),
     ServerRoleUpdate(ServerRoleUpdate),
     ServerRoleDelete(RoleEvent),
     UserUpdate(UserUpdate),
     UserRelationship(UserRelationshipEvent),
     UserPlatformWipe(UserPlatformWipeEvent),
     EmojiCreate(Emoji),
-    EmojiDelete { id: String },
+    EmojiDelete { id: EmojiId },
     Auth(AuthEvent),
 
     /// Represents a received ServerEvent that doesn't have an enum representation
@@ -61,226 +63,387 @@ pub enum ServerEvent {
     Unknown
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl ServerEvent {
+    /// The name of the event's type, as sent by Revolt
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Error { .. } => "Error",
+            Self::Authenticated => "Authenticated",
+            Self::Bulk(_) => "Bulk",
+            Self::Pong(_) => "Pong",
+            Self::Ready(_) => "Ready",
+            Self::Message(_) => "Message",
+            Self::MessageUpdate(_) => "MessageUpdate",
+            Self::MessageAppend(_) => "MessageAppend",
+            Self::MessageDelete(_) => "MessageDelete",
+            Self::MessageReact(_) => "MessageReact",
+            Self::MessageUnreact(_) => "MessageUnreact",
+            Self::MessageRemoveReaction(_) => "MessageRemoveReaction",
+            Self::ChannelCreate(_) => "ChannelCreate",
+            Self::ChannelUpdate(_) => "ChannelUpdate",
+            Self::ChannelDelete { .. } => "ChannelDelete",
+            Self::ChannelGroupJoin(_) => "ChannelGroupJoin",
+            Self::ChannelGroupLeave(_) => "ChannelGroupLeave",
+            Self::ChannelStartTyping(_) => "ChannelStartTyping",
+            Self::ChannelStopTyping(_) => "ChannelStopTyping",
+            Self::ChannelAck(_) => "ChannelAck",
+            Self::ServerCreate(_) => "ServerCreate",
+            Self::ServerUpdate(_) => "ServerUpdate",
+            Self::ServerDelete { .. } => "ServerDelete",
+            Self::ServerMemberUpdate(_) => "ServerMemberUpdate",
+            Self::ServerMemberJoin(_) => "ServerMemberJoin",
+            Self::ServerMemberLeave(_) => "ServerMemberLeave",
+            Self::ServerRoleUpdate(_) => "ServerRoleUpdate",
+            Self::ServerRoleDelete(_) => "ServerRoleDelete",
+            Self::UserUpdate(_) => "UserUpdate",
+            Self::UserRelationship(_) => "UserRelationship",
+            Self::UserPlatformWipe(_) => "UserPlatformWipe",
+            Self::EmojiCreate(_) => "EmojiCreate",
+            Self::EmojiDelete { .. } => "EmojiDelete",
+            Self::Auth(_) => "Auth",
+            Self::Unknown => "Unknown",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DeleteSession {
-    pub user_id: String,
+    pub user_id: UserId,
     pub session_id: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct DeleteAllSessions {
-    pub user_id: String,
-    pub exclude_session_id: String,
+    pub user_id: UserId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclude_session_id: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "event_type")]
 pub enum AuthEvent {
     DeleteSession(DeleteSession),
     DeleteAllSessions(DeleteAllSessions),
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl AuthEvent {
+    /// The id of the user whose session(s) were deleted
+    pub fn get_user_id(&self) -> UserId {
+        match self {
+            Self::DeleteSession(session) => session.user_id.clone(),
+            Self::DeleteAllSessions(sessions) => sessions.user_id.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UserPlatformWipeEvent {
-    pub user_id: String,
-    pub flags: i32,
+    pub user_id: UserId,
+    pub flags: BitFlags<Flag>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct UserRelationshipEvent {
-    pub id: String,
-    user: User,
-    status: RelationshipStatus
+    pub id: UserId,
+    pub user: User,
+    pub status: RelationshipStatus
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum UserClear {
     ProfileContent,
     ProfileBackground,
     StatusText,
-    Avatar
+    StatusPresence,
+    Avatar,
+    DisplayName,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct PartialUser {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub discriminator: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<File>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<PartialStatus>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<UserProfile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub badges: Option<BitFlags<Badge>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flags: Option<BitFlags<Flag>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub online: Option<bool>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct UserUpdate {
+    pub id: UserId,
+    #[serde(default)]
+    pub data: PartialUser,
+    #[serde(default)]
+    pub clear: Vec<UserClear>
+}
+
+impl UserUpdate {
+    /// The presence change contained in this update, if any
+    pub fn get_presence_update(&self) -> Option<PresenceUpdate> {
+        let presence = if self.clear.contains(&UserClear::StatusPresence) {
+            Some(Presence::default())
+        } else {
+            self.data.status.as_ref().and_then(|status| status.presence.clone())
+        };
 
+        if presence.is_none() && self.data.online.is_none() {
+            return None;
+        }
+
+        Some(PresenceUpdate {
+            user_id: self.id.clone(),
+            online: self.data.online,
+            presence,
+        })
+    }
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct UserUpdate {
-    pub id: String,
-    pub data: PartialUser,
-    pub clear: Option<Vec<UserClear>>
+/// A change to a user's online state or presence, dispatched alongside `UserUpdate`
+#[derive(Debug, Clone)]
+pub struct PresenceUpdate {
+    pub user_id: UserId,
+    /// Whether the user is now online, if that changed
+    pub online: Option<bool>,
+    /// The user's new presence, if that changed. A cleared presence is reported as `Invisible`
+    pub presence: Option<Presence>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RoleEvent {
 
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PartialRole {
     #[serde(rename = "role_id")]
-    pub id: String,
+    pub id: RoleId,
     #[serde(rename = "id")]
-    pub server: String,
+    pub server: ServerId,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum RoleClear {
     Colour
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ServerRoleUpdate {
     #[serde(rename = "role_id")]
-    pub id: String,
+    pub id: RoleId,
     #[serde(rename = "id")]
-    pub server: String,
+    pub server: ServerId,
     pub data: PartialRole,
     pub clear: Option<Vec<RoleClear>>
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct ServerMemberId {
-    pub server: String,
-    pub user: String,
-}
+pub type ServerMemberId = MemberId;
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 pub struct PartialServerMember {
-
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub joined_at: Option<Timestamp>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<File>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Vec<RoleId>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<Timestamp>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 pub enum ServerMemberClear {
     Nickname,
-    Avatar
+    Avatar,
+    Roles,
+    Timeout,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ServerMemberUpdate {
     pub id: ServerMemberId,
+    #[serde(default)]
     pub data: PartialServerMember,
-    pub clear: Option<Vec<ServerMemberClear>>
+    #[serde(default)]
+    pub clear: Vec<ServerMemberClear>
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ServerUpdateEvent {
-    pub id: String,
+    pub id: ServerId,
     pub data: PartialServer,
     pub clear: Option<Vec<ClearField>>
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ChannelMessageAcknowledgeEvent {
-    pub id: String,
-    pub user: String,
-    pub message_id: String,
+    pub id: ChannelId,
+    pub user: UserId,
+    pub message_id: MessageId,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Event {
-    pub id: String,
-    pub user: String,
+    pub id: ChannelId,
+    pub user: UserId,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MemberJoinEvent {
+    pub id: ServerId,
+    pub user: UserId,
+}
+
+/// Why a member is no longer in a server
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RemovalReason {
+    #[default]
+    Leave,
+    Kick,
+    Ban,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MemberLeaveEvent {
+    pub id: ServerId,
+    pub user: UserId,
+    #[serde(default)]
+    pub reason: RemovalReason,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub enum ClearField {
     Icon,
     Banner,
     Description,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ChannelUpdateEvent {
-    pub id: String,
+    pub id: ChannelId,
     pub data: PartialChannel,
     pub clear: Vec<ClearField>
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct RemoveReactionEvent {
-    pub id: String,
-    pub channel_id: String,
+    pub id: MessageId,
+    pub channel_id: ChannelId,
     pub emoji_id: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MessageReactEvent {
-    pub id: String,
-    pub channel_id: String,
-    pub user_id: String,
+    pub id: MessageId,
+    pub channel_id: ChannelId,
+    pub user_id: UserId,
     pub emoji_id: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MessageDeleteEvent {
-    pub id: String,
-    pub channel: String,
+    pub id: MessageId,
+    pub channel: ChannelId,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EmbedAppendEvent {
     #[serde(rename = "id")]
-    pub message: String,
-    pub channel: String,
+    pub message: MessageId,
+    pub channel: ChannelId,
     pub append: EmbedAppends,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct EmbedAppends {
     pub embeds: Option<Vec<Embed>>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct MessageUpdateEvent {
-    pub id: String,
-    pub channel: String,
+    pub id: MessageId,
+    pub channel: ChannelId,
     #[serde(default)]
-    pub data: PartialMessage,
+    pub data: MessageUpdateData,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ReadyEvent {
     #[serde(default)]
-    users: Vec<User>,
-    #[serde(default)]
-    servers: Vec<Server>,
-    #[serde(default)]
-    channels: Vec<Channel>,
+    pub users: Vec<User>,
     #[serde(default)]
-    members: Vec<Member>,
+    pub servers: Vec<Server>,
     #[serde(default)]
-    emojis: Option<Vec<Emoji>>
-}
-
-#[derive(Debug, Deserialize, Clone)]
-pub struct Member {
-    #[serde(rename = "_id")]
-    id: MemberId,
-    joined_at: Timestamp,
-    pub nickname: Option<String>,
+    pub channels: Vec<Channel>,
     #[serde(default)]
-    pub avatar: Option<File>,
-    #[serde(default)]
-    pub roles: Vec<String>,
-    #[serde(default)]
-    pub timeout: Option<Timestamp>,
-}
-
-#[derive(Debug, Deserialize, Clone)]
-pub struct MemberId {
-    server: String,
-    user: String,
+    pub members: Vec<Member>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emojis: Option<Vec<Emoji>>
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct BulkEvent {
     #[serde(rename = "v")]
     pub events: Vec<ServerEvent>
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PongEvent {
     pub data: i32
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::user::{Presence, Status, User};
+
+    use super::UserUpdate;
+
+    fn user_update(json: &str) -> UserUpdate {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn status_text_update_keeps_presence() {
+        let update = user_update(r#"{ "id": "01FD58YK5W7QRV5H3D64KTQYX3", "data": { "status": { "text": "Away" } } }"#);
+
+        let mut user = serde_json::from_str::<User>(r#"{
+            "_id": "01FD58YK5W7QRV5H3D64KTQYX3",
+            "username": "insert",
+            "discriminator": "0001",
+            "status": { "presence": "Busy" }
+        }"#).unwrap();
+
+        user.apply(update.data.clone(), &update.clear);
+
+        assert_eq!(user.status, Status { text: Some("Away".to_string()), presence: Some(Presence::Busy) });
+        assert!(update.get_presence_update().is_none());
+    }
+
+    #[test]
+    fn presence_updates() {
+        let update = user_update(r#"{ "id": "01FD58YK5W7QRV5H3D64KTQYX3", "data": { "status": { "presence": "Idle" } } }"#);
+        assert_eq!(update.get_presence_update().unwrap().presence, Some(Presence::Idle));
+
+        let update = user_update(r#"{ "id": "01FD58YK5W7QRV5H3D64KTQYX3", "data": {}, "clear": ["StatusPresence"] }"#);
+        assert_eq!(update.get_presence_update().unwrap().presence, Some(Presence::Invisible));
+
+        let update = user_update(r#"{ "id": "01FD58YK5W7QRV5H3D64KTQYX3", "data": { "online": true } }"#);
+        let presence_update = update.get_presence_update().unwrap();
+        assert_eq!(presence_update.online, Some(true));
+        assert_eq!(presence_update.presence, None);
+    }
+}
@@ -1,25 +1,27 @@
 use serde::{Deserialize, Serialize};
 
+use super::{id::{FileId, MessageId, ServerId, UserId}, is_default};
+
 #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
 pub struct File {
     #[serde(rename = "_id")]
-    pub id: String,
+    pub id: FileId,
     pub tag: String,
     pub filename: String,
     pub metadata: FileMetadata,
     pub content_type: String,
     pub size: i32,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     pub deleted: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     pub reported: bool,
-    #[serde(default)]
-    pub message_id: Option<String>,
-    #[serde(default)]
-    pub user_id: Option<String>,
-    #[serde(default)]
-    pub server_id: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<MessageId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_id: Option<UserId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub server_id: Option<ServerId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub object_id: Option<String>,
 }
 
@@ -0,0 +1,330 @@
+use std::{borrow::Borrow, convert::Infallible, fmt::Display, str::FromStr};
+
+use iso8601_timestamp::{Duration, Timestamp};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// The length of a ULID
+pub const ULID_LENGTH: usize = 26;
+
+/// The characters of Crockford's base32, in order of their value
+const CROCKFORD_ALPHABET: &str = "0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+/// Represents a string that isn't a valid ULID
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum IdError {
+    #[error("An id must be {ULID_LENGTH} characters long, got {0}")]
+    InvalidLength(usize),
+    #[error("`{0}` is not a valid id character")]
+    InvalidCharacter(char),
+    #[error("The id's timestamp overflows")]
+    Overflow,
+}
+
+/// Checks that `id` is a ULID, e.g. `01FD58YK5W7QRV5H3D64NTEB4K`
+pub fn validate_ulid(id: &str) -> Result<(), IdError> {
+    let len = id.chars().count();
+
+    if len != ULID_LENGTH {
+        return Err(IdError::InvalidLength(len));
+    }
+
+    if let Some(c) = id.chars().find(|c| decode_char(*c).is_none()) {
+        return Err(IdError::InvalidCharacter(c));
+    }
+
+    // the timestamp is 48 bits, so the first character can only hold 3 of its 5 bits
+    if id.starts_with(|c| decode_char(c).is_some_and(|value| value > 7)) {
+        return Err(IdError::Overflow);
+    }
+
+    Ok(())
+}
+
+/// Gets the time a ULID was created at, from its first 10 characters
+pub fn ulid_timestamp(id: &str) -> Option<Timestamp> {
+    validate_ulid(id).ok()?;
+
+    let millis = id.chars()
+        .take(10)
+        .try_fold(0i64, |millis, c| Some(millis << 5 | decode_char(c)? as i64))?;
+
+    Timestamp::UNIX_EPOCH.checked_add(Duration::milliseconds(millis))
+}
+
+fn decode_char(c: char) -> Option<u8> {
+    CROCKFORD_ALPHABET.find(c.to_ascii_uppercase())
+        .map(|value| value as u8)
+}
+
+macro_rules! define_id {
+    ($(#[$doc:meta])* $name:ident) => {
+        $(#[$doc])*
+        ///
+        /// Ids received from Revolt are trusted as is, while ones created with `new`,
+        /// [`FromStr`] or [`TryFrom`] are checked to be ULIDs.
+        #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+        #[serde(transparent)]
+        pub struct $name(String);
+
+        impl $name {
+            /// Creates an id, checking that it's a ULID
+            pub fn new(id: &str) -> Result<Self, IdError> {
+                validate_ulid(id)?;
+
+                Ok(Self(id.to_string()))
+            }
+
+            /// Creates an id without checking it, for trusted data such as Revolt's responses
+            pub(crate) fn new_unchecked(id: impl Into<String>) -> Self {
+                Self(id.into())
+            }
+
+            pub fn as_str(&self) -> &str {
+                &self.0
+            }
+
+            pub fn is_valid(&self) -> bool {
+                validate_ulid(&self.0).is_ok()
+            }
+
+            /// The time the object was created at, encoded in its id
+            pub fn created_at(&self) -> Option<Timestamp> {
+                ulid_timestamp(&self.0)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer).map(Self::new_unchecked)
+            }
+        }
+
+        impl Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                write!(f, "{}", self.0)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = IdError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::new(s)
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl Borrow<str> for $name {
+            fn borrow(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl TryFrom<String> for $name {
+            type Error = IdError;
+
+            fn try_from(id: String) -> Result<Self, Self::Error> {
+                validate_ulid(&id)?;
+
+                Ok(Self(id))
+            }
+        }
+
+        impl TryFrom<&str> for $name {
+            type Error = IdError;
+
+            fn try_from(id: &str) -> Result<Self, Self::Error> {
+                Self::new(id)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(id: $name) -> Self {
+                id.0
+            }
+        }
+
+        impl PartialEq<str> for $name {
+            fn eq(&self, other: &str) -> bool {
+                self.0 == other
+            }
+        }
+
+        impl PartialEq<&str> for $name {
+            fn eq(&self, other: &&str) -> bool {
+                self.0 == *other
+            }
+        }
+
+        impl PartialEq<String> for $name {
+            fn eq(&self, other: &String) -> bool {
+                &self.0 == other
+            }
+        }
+    };
+}
+
+define_id!(
+    /// The id of a user
+    UserId
+);
+
+define_id!(
+    /// The id of a channel
+    ChannelId
+);
+
+define_id!(
+    /// The id of a server
+    ServerId
+);
+
+define_id!(
+    /// The id of a message
+    MessageId
+);
+
+define_id!(
+    /// The id of a role, unique within its server
+    RoleId
+);
+
+define_id!(
+    /// The id of a custom emoji
+    EmojiId
+);
+
+/// The id of a file uploaded to Autumn
+///
+/// Unlike the other ids these aren't ULIDs, e.g. `ymJvsPm3g9GcsLd6Ks6tLUy2uxsMNXNrdZq-GMSpKU`,
+/// so they're never validated and carry no creation time.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FileId(String);
+
+impl FileId {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self(id.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for FileId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl FromStr for FileId {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::new(s))
+    }
+}
+
+impl AsRef<str> for FileId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for FileId {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<String> for FileId {
+    fn from(id: String) -> Self {
+        Self(id)
+    }
+}
+
+impl From<&str> for FileId {
+    fn from(id: &str) -> Self {
+        Self::new(id)
+    }
+}
+
+impl From<FileId> for String {
+    fn from(id: FileId) -> Self {
+        id.0
+    }
+}
+
+impl PartialEq<str> for FileId {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for FileId {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iso8601_timestamp::Timestamp;
+
+    use super::{ulid_timestamp, FileId, IdError, MessageId, UserId};
+
+    #[test]
+    fn checks_ids_in_conversions() {
+        let id = "01FD58YK5W7QRV5H3D64NTEB4K";
+
+        assert_eq!(UserId::try_from(id).unwrap(), id);
+        assert_eq!(UserId::try_from(id.to_string()).unwrap(), id);
+        assert_eq!(id.parse::<UserId>().unwrap(), id);
+
+        assert_eq!(UserId::try_from(""), Err(IdError::InvalidLength(0)));
+        assert_eq!(UserId::try_from("01FD58YK5W7QRV5H3D64NTEB4U"), Err(IdError::InvalidCharacter('U')));
+        assert_eq!(UserId::try_from("81FD58YK5W7QRV5H3D64NTEB4K"), Err(IdError::Overflow));
+    }
+
+    #[test]
+    fn trusts_received_ids() {
+        let id: UserId = serde_json::from_str("\"not an id\"").unwrap();
+
+        assert!(!id.is_valid());
+        assert_eq!(serde_json::to_string(&id).unwrap(), "\"not an id\"");
+    }
+
+    #[test]
+    fn reads_the_timestamp_of_ulids() {
+        assert_eq!(ulid_timestamp("00000000000000000000000000"), Some(Timestamp::UNIX_EPOCH));
+        assert_eq!(ulid_timestamp("01FD58YK5W7QRV5H3D64NTEB4K"), Timestamp::parse("2021-08-15T16:05:10.460Z"));
+        assert_eq!(ulid_timestamp("01fd58yk5w7qrv5h3d64nteb4k"), Timestamp::parse("2021-08-15T16:05:10.460Z"));
+
+        assert_eq!(ulid_timestamp("7ZZZZZZZZZZZZZZZZZZZZZZZZZ"), Timestamp::UNIX_EPOCH.checked_add(iso8601_timestamp::Duration::milliseconds((1 << 48) - 1)));
+        assert_eq!(ulid_timestamp("not a ulid"), None);
+        assert_eq!(ulid_timestamp("81FD58YK5W7QRV5H3D64NTEB4K"), None);
+    }
+
+    #[test]
+    fn ids_know_when_they_were_created() {
+        let id = MessageId::new("01FD58YK5W7QRV5H3D64NTEB4K").unwrap();
+        assert_eq!(id.created_at(), Timestamp::parse("2021-08-15T16:05:10.460Z"));
+
+        let id: MessageId = serde_json::from_str("\"not an id\"").unwrap();
+        assert_eq!(id.created_at(), None);
+    }
+
+    #[test]
+    fn accepts_any_file_id() {
+        let id: FileId = "ymJvsPm3g9GcsLd6Ks6tLUy2uxsMNXNrdZq-GMSpKU".parse().unwrap();
+
+        assert_eq!(id, "ymJvsPm3g9GcsLd6Ks6tLUy2uxsMNXNrdZq-GMSpKU");
+        assert_eq!(serde_json::to_string(&id).unwrap(), "\"ymJvsPm3g9GcsLd6Ks6tLUy2uxsMNXNrdZq-GMSpKU\"");
+    }
+}
@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+use crate::http::prelude::{HttpClient, HttpError};
+
+use super::{channel::Channel, content, file::File, server::{Server, ServerFlag}, user::User, bitfield::BitFlags, id::{ChannelId, ServerId, UserId}};
+
+/// An invite to a server channel or a group
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum Invite {
+    Server {
+        #[serde(rename = "_id")]
+        code: String,
+        server: ServerId,
+        creator: UserId,
+        channel: ChannelId,
+    },
+    Group {
+        #[serde(rename = "_id")]
+        code: String,
+        creator: UserId,
+        channel: ChannelId,
+    },
+}
+
+impl Invite {
+    pub fn get_code(&self) -> &str {
+        match self {
+            Self::Server { code, .. } | Self::Group { code, .. } => code,
+        }
+    }
+
+    pub fn get_creator(&self) -> &UserId {
+        match self {
+            Self::Server { creator, .. } | Self::Group { creator, .. } => creator,
+        }
+    }
+
+    pub fn get_channel_id(&self) -> &ChannelId {
+        match self {
+            Self::Server { channel, .. } | Self::Group { channel, .. } => channel,
+        }
+    }
+
+    /// A link to the invite, e.g. `https://rvlt.gg/Testers`
+    pub fn get_url(&self) -> String {
+        format!("https://rvlt.gg/{}", self.get_code())
+    }
+
+    pub async fn delete(&self, ctx: &impl AsRef<HttpClient>) -> Result<(), HttpError> {
+        ctx.as_ref().delete_invite(self.get_code()).await
+    }
+}
+
+/// The public information about an invite, shown before joining it
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(tag = "type")]
+pub enum InviteInfo {
+    Server {
+        code: String,
+        server_id: ServerId,
+        server_name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        server_icon: Option<File>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        server_banner: Option<File>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        server_flags: Option<BitFlags<ServerFlag>>,
+        channel_id: ChannelId,
+        channel_name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        channel_description: Option<String>,
+        /// The name of the invite's creator
+        user_name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        user_avatar: Option<File>,
+        member_count: i64,
+    },
+    Group {
+        code: String,
+        channel_id: ChannelId,
+        channel_name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        channel_description: Option<String>,
+        /// The name of the invite's creator
+        user_name: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        user_avatar: Option<File>,
+    },
+}
+
+impl InviteInfo {
+    pub fn get_code(&self) -> &str {
+        match self {
+            Self::Server { code, .. } | Self::Group { code, .. } => code,
+        }
+    }
+
+    pub fn get_channel_name(&self) -> &str {
+        match self {
+            Self::Server { channel_name, .. } | Self::Group { channel_name, .. } => channel_name,
+        }
+    }
+
+    /// The name of the server the invite is for, `None` for group invites
+    pub fn get_server_name(&self) -> Option<&str> {
+        match self {
+            Self::Server { server_name, .. } => Some(server_name),
+            Self::Group { .. } => None,
+        }
+    }
+
+    /// How many members the server has, `None` for group invites
+    pub fn get_member_count(&self) -> Option<i64> {
+        match self {
+            Self::Server { member_count, .. } => Some(*member_count),
+            Self::Group { .. } => None,
+        }
+    }
+}
+
+/// What was joined through an invite
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type")]
+pub enum InviteJoin {
+    Server {
+        channels: Vec<Channel>,
+        server: Server,
+    },
+    Group {
+        channel: Channel,
+        users: Vec<User>,
+    },
+}
+
+/// Gets an invite code from an invite link, e.g. `https://rvlt.gg/Testers` or
+/// `https://app.revolt.chat/invite/Testers`, or from a code on its own
+pub fn parse_invite(input: &str) -> Option<String> {
+    let input = input.trim();
+
+    content::parse_invite_code(input).or_else(|| {
+        is_invite_code(input).then(|| input.to_string())
+    })
+}
+
+/// Checks that `code` looks like an invite code, which is only ever alphanumeric
+pub fn is_invite_code(code: &str) -> bool {
+    !code.is_empty() && code.chars().all(|c| c.is_ascii_alphanumeric())
+}
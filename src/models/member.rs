@@ -0,0 +1,152 @@
+use iso8601_timestamp::Timestamp;
+use serde::{Deserialize, Serialize};
+
+use crate::http::prelude::{HttpClient, HttpError};
+
+use super::{file::File, user::User, id::{RoleId, ServerId, UserId}, events::server::{PartialServerMember, ServerMemberClear}};
+
+/// Identifies a member by the server they're in and their user id
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MemberId {
+    pub server: ServerId,
+    pub user: UserId,
+}
+
+/// A user's membership of a server
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Member {
+    #[serde(rename = "_id")]
+    pub id: MemberId,
+    pub joined_at: Timestamp,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub avatar: Option<File>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roles: Vec<RoleId>,
+    /// Until when the member is timed out
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<Timestamp>,
+}
+
+impl Member {
+    /// Creates a member that just joined a server
+    pub fn new(server_id: &ServerId, user_id: &UserId) -> Self {
+        Self {
+            id: MemberId {
+                server: server_id.clone(),
+                user: user_id.clone(),
+            },
+            joined_at: Timestamp::now_utc(),
+            nickname: None,
+            avatar: None,
+            roles: Vec::new(),
+            timeout: None,
+        }
+    }
+
+    /// Applies a `ServerMemberUpdate` to this member
+    pub fn apply(&mut self, data: PartialServerMember, clear: &[ServerMemberClear]) {
+        for field in clear {
+            match field {
+                ServerMemberClear::Nickname => self.nickname = None,
+                ServerMemberClear::Avatar => self.avatar = None,
+                ServerMemberClear::Roles => self.roles.clear(),
+                ServerMemberClear::Timeout => self.timeout = None,
+            }
+        }
+
+        if let Some(joined_at) = data.joined_at {
+            self.joined_at = joined_at;
+        }
+
+        if let Some(nickname) = data.nickname {
+            self.nickname = Some(nickname);
+        }
+
+        if let Some(avatar) = data.avatar {
+            self.avatar = Some(avatar);
+        }
+
+        if let Some(roles) = data.roles {
+            self.roles = roles;
+        }
+
+        if let Some(timeout) = data.timeout {
+            self.timeout = Some(timeout);
+        }
+    }
+
+    /// Checks if the member is currently timed out
+    pub fn is_timed_out(&self) -> bool {
+        self.timeout.is_some_and(|timeout| timeout > Timestamp::now_utc())
+    }
+
+    /// The name shown for the member: their nickname, falling back
+    /// to the user's display name and then their username
+    pub fn display_name(&self, user: &User) -> String {
+        self.nickname.clone()
+            .unwrap_or_else(|| user.get_display_name())
+    }
+}
+
+impl Member {
+    /// Removes the member from the server, they can rejoin with an invite
+    pub async fn kick(&self, ctx: &impl AsRef<HttpClient>) -> Result<(), HttpError> {
+        ctx.as_ref().kick_member(&self.id.server, &self.id.user).await
+    }
+
+    pub async fn ban(&self, ctx: &impl AsRef<HttpClient>, reason: Option<&str>) -> Result<Ban, HttpError> {
+        ctx.as_ref().ban_member(&self.id.server, &self.id.user, reason).await
+    }
+
+    /// Times the member out until `until`, returning the updated member
+    pub async fn timeout(&self, ctx: &impl AsRef<HttpClient>, until: Timestamp) -> Result<Member, HttpError> {
+        let edit = EditMember {
+            timeout: Some(until),
+            ..Default::default()
+        };
+
+        ctx.as_ref().edit_member(&self.id.server, &self.id.user, edit).await
+    }
+
+    /// Ends the member's timeout, returning the updated member
+    pub async fn remove_timeout(&self, ctx: &impl AsRef<HttpClient>) -> Result<Member, HttpError> {
+        let edit = EditMember {
+            remove: vec![ServerMemberClear::Timeout],
+            ..Default::default()
+        };
+
+        ctx.as_ref().edit_member(&self.id.server, &self.id.user, edit).await
+    }
+}
+
+/// The changes to make to a member, fields left as `None` are kept as is
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct EditMember {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nickname: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub roles: Option<Vec<RoleId>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<Timestamp>,
+    /// Fields to clear
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remove: Vec<ServerMemberClear>,
+}
+
+/// Every member of a server, along with their users
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct MemberList {
+    pub members: Vec<Member>,
+    pub users: Vec<User>,
+}
+
+/// A user banned from a server
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Ban {
+    #[serde(rename = "_id")]
+    pub id: MemberId,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
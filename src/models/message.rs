@@ -1,17 +1,19 @@
-use std::collections::{HashMap, HashSet};
+use std::{collections::{HashMap, HashSet}, fmt::Display};
 
 use iso8601_timestamp::Timestamp;
 use serde::{Deserialize, Serialize};
 
-use super::{file::File, embed::Embed};
+use crate::{cache::Cache, client::colors::{Colour, ColourError}, http::prelude::{HttpClient, HttpError}};
 
-#[derive(Debug, Deserialize, PartialEq, Clone)]
+use super::{file::File, embed::{Embed, SendableEmbed, EmbedValidationError}, content::{self, ContentEntity}, id::{ChannelId, EmojiId, FileId, MessageId, UserId}, is_default};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct WebhookInfo {
     name: String,
     avatar: String,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 #[serde(tag = "type")]
 pub enum SystemEventMessage {
     Text {
@@ -19,104 +21,674 @@ pub enum SystemEventMessage {
     },
     #[serde(rename = "user_added")]
     UserAdded {
-        id: String,
-        by: String,
+        id: UserId,
+        by: UserId,
     },
     #[serde(rename = "user_remove")]
     UserRemove {
-        id: String,
-        by: String,
+        id: UserId,
+        by: UserId,
     },
     #[serde(rename = "user_joined")]
     UserJoined {
-        id: String,
+        id: UserId,
     },
     #[serde(rename = "user_left")]
     UserLeft {
-        id: String,
+        id: UserId,
     },
     #[serde(rename = "user_kicked")]
     UserKicked {
-        id: String,
+        id: UserId,
     },
     #[serde(rename = "user_banned")]
     UserBanned {
-        id: String,
+        id: UserId,
     },
     #[serde(rename = "channel_renamed")]
     ChannelRenamed {
         name: String,
-        by: String,
+        by: UserId,
     },
     #[serde(rename = "channel_description_changed")]
     ChannelDescriptionChanged {
-        by: String,
+        by: UserId,
     },
     #[serde(rename = "channel_icon_changed")]
     ChannelIconChanged {
-        by: String,
+        by: UserId,
     },
     #[serde(rename = "channel_ownership_changed")]
     ChannelOwnershipChanged {
-        from: String,
-        to: String,
+        from: UserId,
+        to: UserId,
     },
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq, Default)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Message {
     #[serde(rename = "_id")]
-    pub id: String,
-    #[serde(default)]
+    pub id: MessageId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub nonce: Option<String>,
-    pub channel: String,
-    pub author: String,
-    #[serde(default)]
+    pub channel: ChannelId,
+    pub author: UserId,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub webhook: Option<WebhookInfo>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub system: Option<SystemEventMessage>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub attachments: Vec<File>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub edited: Option<Timestamp>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub embeds: Vec<Embed>,
-    #[serde(default)]
-    pub replies: Vec<String>,
-    #[serde(default)]
-    pub reactions: HashMap<String, HashSet<String>>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub replies: Vec<MessageId>,
+    /// The users who reacted, keyed by emoji
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub reactions: HashMap<String, HashSet<UserId>>,
+    #[serde(default, skip_serializing_if = "is_default")]
     pub interactions: Interactions,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub masquerade: Option<Masquerade>
 }
 
+impl Message {
+    /// Applies the fields set in a `MessageUpdate` to this message
+    pub fn apply(&mut self, data: MessageUpdateData) {
+        if let Some(content) = data.content {
+            self.content = Some(content);
+        }
+
+        if let Some(edited) = data.edited {
+            self.edited = Some(edited);
+        }
+
+        if let Some(embeds) = data.embeds {
+            self.embeds = embeds;
+        }
+
+        if let Some(attachments) = data.attachments {
+            self.attachments = attachments;
+        }
+
+        if let Some(reactions) = data.reactions {
+            self.reactions = reactions;
+        }
+
+        if let Some(interactions) = data.interactions {
+            self.interactions = interactions;
+        }
+
+        if let Some(masquerade) = data.masquerade {
+            self.masquerade = Some(masquerade);
+        }
+    }
+
+    /// Finds the mentions, custom emoji, links and invites in the message's content
+    pub fn parse_content(&self) -> Vec<ContentEntity> {
+        content::parse_content(self.content.as_deref().unwrap_or_default())
+    }
+
+    /// The message's content with mentions replaced by the names of what they mention
+    pub fn clean_content(&self, cache: &Cache) -> String {
+        let server_id = cache.get_channel(&self.channel)
+            .and_then(|channel| channel.get_server_id());
+
+        content::clean_content(self.content.as_deref().unwrap_or_default(), cache, server_id.as_ref())
+    }
+
+    /// Compares this message's content with a newer version of it, word by word.
+    /// Returns `None` if the content didn't change
+    pub fn diff_content(&self, new: &Message) -> Option<Vec<ContentChange>> {
+        let old_content = self.content.as_deref().unwrap_or_default();
+        let new_content = new.content.as_deref().unwrap_or_default();
+
+        if old_content == new_content {
+            return None;
+        }
+
+        Some(diff_words(old_content, new_content))
+    }
+}
+
+impl Message {
+    /// Replies to this message, `mention` pings its author
+    pub async fn reply(&self, ctx: &impl AsRef<HttpClient>, content: &str, mention: bool) -> Result<Message, HttpError> {
+        let reply = PartialMessage {
+            content: Some(content.to_string()),
+            replies: Some(vec![Reply { id: self.id.clone(), mention }]),
+            ..Default::default()
+        };
+
+        ctx.as_ref().send_msg_in_channel(&self.channel, reply).await
+    }
+
+    /// Replaces the content of this message, which must have been sent by the client
+    pub async fn edit(&self, ctx: &impl AsRef<HttpClient>, content: &str) -> Result<Message, HttpError> {
+        let edit = MessageEdit {
+            content: Some(content.to_string()),
+            ..Default::default()
+        };
+
+        ctx.as_ref().edit_message(&self.channel, &self.id, edit).await
+    }
+
+    pub async fn delete(&self, ctx: &impl AsRef<HttpClient>) -> Result<(), HttpError> {
+        ctx.as_ref().delete_message(&self.channel, &self.id).await
+    }
+
+    /// Reacts with a unicode emoji or the id of a custom emoji
+    pub async fn react(&self, ctx: &impl AsRef<HttpClient>, emoji: &str) -> Result<(), HttpError> {
+        ctx.as_ref().add_reaction(&self.channel, &self.id, emoji).await
+    }
+
+    /// Removes the client's own reaction
+    pub async fn unreact(&self, ctx: &impl AsRef<HttpClient>, emoji: &str) -> Result<(), HttpError> {
+        ctx.as_ref().remove_reaction(&self.channel, &self.id, emoji).await
+    }
+}
+
+/// A section of a message's content in a [`Message::diff_content`] result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ContentChange {
+    Unchanged(String),
+    Added(String),
+    Removed(String),
+}
+
+impl ContentChange {
+    pub fn text(&self) -> &str {
+        match self {
+            Self::Unchanged(text) | Self::Added(text) | Self::Removed(text) => text,
+        }
+    }
+}
+
+fn diff_words(old: &str, new: &str) -> Vec<ContentChange> {
+    let old_words = old.split_inclusive(char::is_whitespace).collect::<Vec<_>>();
+    let new_words = new.split_inclusive(char::is_whitespace).collect::<Vec<_>>();
+
+    // lengths of the longest common subsequences of every pair of suffixes
+    let mut lcs = vec![vec![0usize; new_words.len() + 1]; old_words.len() + 1];
+
+    for i in (0..old_words.len()).rev() {
+        for j in (0..new_words.len()).rev() {
+            lcs[i][j] = if old_words[i] == new_words[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let mut changes: Vec<ContentChange> = Vec::new();
+    let mut push = |change: ContentChange| {
+        match (changes.last_mut(), &change) {
+            (Some(ContentChange::Unchanged(prev)), ContentChange::Unchanged(text))
+            | (Some(ContentChange::Added(prev)), ContentChange::Added(text))
+            | (Some(ContentChange::Removed(prev)), ContentChange::Removed(text)) => prev.push_str(text),
+            _ => changes.push(change),
+        }
+    };
+
+    let (mut i, mut j) = (0, 0);
+
+    while i < old_words.len() || j < new_words.len() {
+        if i < old_words.len() && j < new_words.len() && old_words[i] == new_words[j] {
+            push(ContentChange::Unchanged(old_words[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if j < new_words.len() && (i == old_words.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            push(ContentChange::Added(new_words[j].to_string()));
+            j += 1;
+        } else {
+            push(ContentChange::Removed(old_words[i].to_string()));
+            i += 1;
+        }
+    }
+
+    changes
+}
+
+/// The fields of a message changed by a `MessageUpdate` event
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
-pub struct PartialMessage {
+pub struct MessageUpdateData {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
-    pub attachments: Option<Vec<File>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edited: Option<Timestamp>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub embeds: Option<Vec<Embed>>,
-    pub replies: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Vec<File>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reactions: Option<HashMap<String, HashSet<UserId>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interactions: Option<Interactions>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub masquerade: Option<Masquerade>,
+}
+
+/// A message to send, usually built with [`MessageBuilder`](super::message_builder::MessageBuilder)
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct PartialMessage {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    /// Ids of files uploaded to Autumn
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub attachments: Option<Vec<FileId>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embeds: Option<Vec<SendableEmbed>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replies: Option<Vec<Reply>>,
     /// Name and / or avatar overrides for this message
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub masquerade: Option<Masquerade>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interactions: Option<Interactions>,
+}
+
+impl PartialMessage {
+    /// Checks the message against Revolt's limits, so it isn't rejected once sent
+    pub fn validate(&self) -> Result<(), MessageValidationError> {
+        let content = self.content.as_deref().unwrap_or_default();
+        let attachments = self.attachments.as_ref().map_or(0, Vec::len);
+        let embeds = self.embeds.as_ref().map_or(0, Vec::len);
+        let replies = self.replies.as_ref().map_or(0, Vec::len);
+
+        if content.trim().is_empty() && attachments == 0 && embeds == 0 {
+            return Err(MessageValidationError::EmptyMessage);
+        }
+
+        if content.chars().count() > MAX_CONTENT_LENGTH {
+            return Err(MessageValidationError::ContentTooLong { max: MAX_CONTENT_LENGTH });
+        }
+
+        if attachments > MAX_ATTACHMENTS {
+            return Err(MessageValidationError::TooManyAttachments { max: MAX_ATTACHMENTS });
+        }
+
+        if embeds > MAX_EMBEDS {
+            return Err(MessageValidationError::TooManyEmbeds { max: MAX_EMBEDS });
+        }
+
+        for embed in self.embeds.iter().flatten() {
+            embed.validate()?;
+        }
+
+        if replies > MAX_REPLIES {
+            return Err(MessageValidationError::TooManyReplies { max: MAX_REPLIES });
+        }
+
+        if let Some(masquerade) = &self.masquerade {
+            if let Some(name) = &masquerade.name {
+                if !(1..=MAX_MASQUERADE_NAME_LENGTH).contains(&name.chars().count()) {
+                    return Err(MessageValidationError::InvalidMasqueradeName { max: MAX_MASQUERADE_NAME_LENGTH });
+                }
+            }
+
+            if let Some(colour) = &masquerade.colour {
+                colour.validate()?;
+            }
+        }
+
+        if let Some(interactions) = &self.interactions {
+            if interactions.reactions.len() > MAX_INTERACTION_REACTIONS {
+                return Err(MessageValidationError::TooManyReactions { max: MAX_INTERACTION_REACTIONS });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// The maximum number of characters in a message
+pub const MAX_CONTENT_LENGTH: usize = 2000;
+/// The maximum number of files attached to a message
+pub const MAX_ATTACHMENTS: usize = 5;
+/// The maximum number of embeds in a message
+pub const MAX_EMBEDS: usize = 5;
+/// The maximum number of messages a message can reply to
+pub const MAX_REPLIES: usize = 5;
+/// The maximum number of characters in a masquerade's name
+pub const MAX_MASQUERADE_NAME_LENGTH: usize = 32;
+/// The maximum number of reactions suggested by a message's interactions
+pub const MAX_INTERACTION_REACTIONS: usize = 20;
+
+/// The maximum number of messages fetched or deleted in one request
+pub const MAX_MESSAGE_BATCH: usize = 100;
+
+/// Which messages to fetch from a channel's history
+#[derive(Debug, Clone, Default)]
+pub struct MessageQuery {
+    /// How many messages to fetch, up to [`MAX_MESSAGE_BATCH`]
+    pub limit: Option<usize>,
+    /// Only fetch messages sent before this one
+    pub before: Option<MessageId>,
+    /// Only fetch messages sent after this one
+    pub after: Option<MessageId>,
+    pub sort: Option<MessageSort>,
+    /// Fetch the messages around this one, `before` and `after` are ignored when set
+    pub nearby: Option<MessageId>,
+}
+
+impl MessageQuery {
+    /// The latest `limit` messages, newest first
+    pub fn latest(limit: usize) -> Self {
+        Self {
+            limit: Some(limit),
+            sort: Some(MessageSort::Latest),
+            ..Default::default()
+        }
+    }
+
+    pub(crate) fn to_query_string(&self) -> String {
+        let mut query = url::form_urlencoded::Serializer::new(String::new());
+
+        if let Some(limit) = self.limit {
+            query.append_pair("limit", &limit.min(MAX_MESSAGE_BATCH).to_string());
+        }
+
+        if let Some(before) = &self.before {
+            query.append_pair("before", before.as_str());
+        }
+
+        if let Some(after) = &self.after {
+            query.append_pair("after", after.as_str());
+        }
+
+        if let Some(sort) = self.sort {
+            query.append_pair("sort", sort.as_str());
+        }
+
+        if let Some(nearby) = &self.nearby {
+            query.append_pair("nearby", nearby.as_str());
+        }
+
+        query.finish()
+    }
+}
+
+/// The order fetched messages are returned in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageSort {
+    Relevance,
+    Latest,
+    Oldest,
+}
+
+impl MessageSort {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Relevance => "Relevance",
+            Self::Latest => "Latest",
+            Self::Oldest => "Oldest",
+        }
+    }
+}
+
+/// The changes to make to a sent message, fields left as `None` are kept as is
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct MessageEdit {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub content: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub embeds: Option<Vec<SendableEmbed>>,
+}
+
+impl MessageEdit {
+    /// Checks the edit against Revolt's limits
+    pub fn validate(&self) -> Result<(), MessageValidationError> {
+        if self.content.as_ref().is_some_and(|content| content.chars().count() > MAX_CONTENT_LENGTH) {
+            return Err(MessageValidationError::ContentTooLong { max: MAX_CONTENT_LENGTH });
+        }
+
+        if self.embeds.as_ref().is_some_and(|embeds| embeds.len() > MAX_EMBEDS) {
+            return Err(MessageValidationError::TooManyEmbeds { max: MAX_EMBEDS });
+        }
+
+        for embed in self.embeds.iter().flatten() {
+            embed.validate()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Represents a message that would be rejected by Revolt
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum MessageValidationError {
+    #[error("Cannot send an empty message")]
+    EmptyMessage,
+    #[error("The content is too long, the maximum is {max} characters")]
+    ContentTooLong { max: usize },
+    #[error("Too many attachments, the maximum is {max}")]
+    TooManyAttachments { max: usize },
+    #[error("Too many embeds, the maximum is {max}")]
+    TooManyEmbeds { max: usize },
+    #[error("Too many replies, the maximum is {max}")]
+    TooManyReplies { max: usize },
+    #[error("The masquerade name must be between 1 and {max} characters")]
+    InvalidMasqueradeName { max: usize },
+    #[error("Too many interaction reactions, the maximum is {max}")]
+    TooManyReactions { max: usize },
+    #[error(transparent)]
+    InvalidEmbed(#[from] EmbedValidationError),
+    #[error("Invalid masquerade colour: {0}")]
+    InvalidColour(#[from] ColourError),
+}
+
+/// A message being replied to
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Reply {
+    pub id: MessageId,
+    /// Whether the author of the replied message is mentioned
+    pub mention: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
 pub struct Masquerade {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub avatar: Option<String>,
-    #[serde(default)]
-    pub colour: Option<String>
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub colour: Option<Colour>
 }
 
-#[derive(Debug, Deserialize, Default, PartialEq, Clone)]
+#[derive(Debug, Serialize, Deserialize, Default, PartialEq, Clone)]
 pub struct Interactions {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub reactions: Vec<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     pub restrict_reactions: bool
-}
\ No newline at end of file
+}
+
+/// An emoji to react with, either a unicode emoji or a custom emoji
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ReactionEmoji {
+    Unicode(String),
+    Custom(EmojiId),
+}
+
+impl Display for ReactionEmoji {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Unicode(emoji) => write!(f, "{emoji}"),
+            Self::Custom(id) => write!(f, "{id}"),
+        }
+    }
+}
+
+impl From<EmojiId> for ReactionEmoji {
+    fn from(id: EmojiId) -> Self {
+        Self::Custom(id)
+    }
+}
+
+impl From<&EmojiId> for ReactionEmoji {
+    fn from(id: &EmojiId) -> Self {
+        Self::Custom(id.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{embed::SendableEmbed, id::FileId};
+
+    use super::*;
+
+    fn message(json: &str) -> Message {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn diffs_words() {
+        assert_eq!(diff_words("hello world", "hello there world"), vec![
+            ContentChange::Unchanged("hello ".to_string()),
+            ContentChange::Added("there ".to_string()),
+            ContentChange::Unchanged("world".to_string()),
+        ]);
+
+        assert_eq!(diff_words("a b c", "a c"), vec![
+            ContentChange::Unchanged("a ".to_string()),
+            ContentChange::Removed("b ".to_string()),
+            ContentChange::Unchanged("c".to_string()),
+        ]);
+
+        assert_eq!(diff_words("", "new"), vec![ContentChange::Added("new".to_string())]);
+        assert_eq!(diff_words("old", ""), vec![ContentChange::Removed("old".to_string())]);
+    }
+
+    #[test]
+    fn diff_joins_the_versions_back_together() {
+        let changes = diff_words("the quick brown fox", "the slow brown dog jumps");
+
+        let old = changes.iter()
+            .filter(|change| !matches!(change, ContentChange::Added(_)))
+            .map(ContentChange::text)
+            .collect::<String>();
+
+        let new = changes.iter()
+            .filter(|change| !matches!(change, ContentChange::Removed(_)))
+            .map(ContentChange::text)
+            .collect::<String>();
+
+        assert_eq!(old, "the quick brown fox");
+        assert_eq!(new, "the slow brown dog jumps");
+    }
+
+    #[test]
+    fn unchanged_content_has_no_diff() {
+        let msg = message(r#"{ "_id": "01GXYZ0000000000000000000A", "channel": "01GXYZ0000000000000000000B", "author": "01GXYZ0000000000000000000C", "content": "hi" }"#);
+
+        assert_eq!(msg.diff_content(&msg), None);
+    }
+
+    #[test]
+    fn applies_every_updated_field() {
+        let mut msg = message(r#"{ "_id": "01GXYZ0000000000000000000A", "channel": "01GXYZ0000000000000000000B", "author": "01GXYZ0000000000000000000C", "content": "hi" }"#);
+
+        let data = serde_json::from_str(r#"{
+            "content": "hello",
+            "edited": "2023-04-01T12:00:00.000Z",
+            "masquerade": { "name": "Someone" },
+            "reactions": { "👍": ["01GXYZ0000000000000000000C"] },
+            "interactions": { "reactions": ["👍"], "restrict_reactions": true }
+        }"#).unwrap();
+
+        msg.apply(data);
+
+        assert_eq!(msg.content.as_deref(), Some("hello"));
+        assert!(msg.edited.is_some());
+        assert_eq!(msg.masquerade.and_then(|masquerade| masquerade.name).as_deref(), Some("Someone"));
+        assert_eq!(msg.reactions["👍"].len(), 1);
+        assert!(msg.interactions.restrict_reactions);
+    }
+
+    fn text(content: &str) -> PartialMessage {
+        PartialMessage { content: Some(content.to_string()), ..Default::default() }
+    }
+
+    fn embed() -> SendableEmbed {
+        SendableEmbed { title: Some("title".to_string()), ..Default::default() }
+    }
+
+    #[test]
+    fn rejects_empty_messages() {
+        assert_eq!(PartialMessage::default().validate(), Err(MessageValidationError::EmptyMessage));
+        assert_eq!(text(" \n").validate(), Err(MessageValidationError::EmptyMessage));
+
+        let attachment_only = PartialMessage { attachments: Some(vec![FileId::new("ymJvsPm3g9GcsLd6Ks6tLUy2uxsMNXNrdZq-GMSpKU")]), ..Default::default() };
+        assert_eq!(attachment_only.validate(), Ok(()));
+
+        let embed_only = PartialMessage { embeds: Some(vec![embed()]), ..Default::default() };
+        assert_eq!(embed_only.validate(), Ok(()));
+    }
+
+    #[test]
+    fn limits_content_length() {
+        assert_eq!(text(&"a".repeat(MAX_CONTENT_LENGTH)).validate(), Ok(()));
+        assert_eq!(text(&"a".repeat(MAX_CONTENT_LENGTH + 1)).validate(), Err(MessageValidationError::ContentTooLong { max: MAX_CONTENT_LENGTH }));
+        // characters are counted rather than bytes
+        assert_eq!(text(&"é".repeat(MAX_CONTENT_LENGTH)).validate(), Ok(()));
+    }
+
+    #[test]
+    fn limits_attachments_embeds_and_replies() {
+        let file_id = FileId::new("ymJvsPm3g9GcsLd6Ks6tLUy2uxsMNXNrdZq-GMSpKU");
+        let reply = Reply { id: MessageId::new("01GXYZ0000000000000000000A").unwrap(), mention: false };
+
+        let attachments = |count| PartialMessage { attachments: Some(vec![file_id.clone(); count]), ..text("hi") };
+        assert_eq!(attachments(MAX_ATTACHMENTS).validate(), Ok(()));
+        assert_eq!(attachments(MAX_ATTACHMENTS + 1).validate(), Err(MessageValidationError::TooManyAttachments { max: MAX_ATTACHMENTS }));
+
+        let embeds = |count| PartialMessage { embeds: Some(vec![embed(); count]), ..text("hi") };
+        assert_eq!(embeds(MAX_EMBEDS).validate(), Ok(()));
+        assert_eq!(embeds(MAX_EMBEDS + 1).validate(), Err(MessageValidationError::TooManyEmbeds { max: MAX_EMBEDS }));
+
+        let replies = |count| PartialMessage { replies: Some(vec![reply.clone(); count]), ..text("hi") };
+        assert_eq!(replies(MAX_REPLIES).validate(), Ok(()));
+        assert_eq!(replies(MAX_REPLIES + 1).validate(), Err(MessageValidationError::TooManyReplies { max: MAX_REPLIES }));
+    }
+
+    #[test]
+    fn limits_masquerade_names() {
+        let masquerade = |name: &str| PartialMessage {
+            masquerade: Some(Masquerade { name: Some(name.to_string()), ..Default::default() }),
+            ..text("hi")
+        };
+
+        assert_eq!(masquerade(&"a".repeat(MAX_MASQUERADE_NAME_LENGTH)).validate(), Ok(()));
+        assert_eq!(masquerade(&"a".repeat(MAX_MASQUERADE_NAME_LENGTH + 1)).validate(), Err(MessageValidationError::InvalidMasqueradeName { max: MAX_MASQUERADE_NAME_LENGTH }));
+        assert_eq!(masquerade("").validate(), Err(MessageValidationError::InvalidMasqueradeName { max: MAX_MASQUERADE_NAME_LENGTH }));
+    }
+
+    #[test]
+    fn limits_interaction_reactions() {
+        let reactions = |count| PartialMessage {
+            interactions: Some(Interactions { reactions: vec!["👍".to_string(); count], restrict_reactions: false }),
+            ..text("hi")
+        };
+
+        assert_eq!(reactions(MAX_INTERACTION_REACTIONS).validate(), Ok(()));
+        assert_eq!(reactions(MAX_INTERACTION_REACTIONS + 1).validate(), Err(MessageValidationError::TooManyReactions { max: MAX_INTERACTION_REACTIONS }));
+    }
+
+    #[test]
+    fn limits_edits() {
+        assert_eq!(MessageEdit::default().validate(), Ok(()));
+
+        let content = |content: String| MessageEdit { content: Some(content), ..Default::default() };
+        assert_eq!(content("a".repeat(MAX_CONTENT_LENGTH)).validate(), Ok(()));
+        assert_eq!(content("a".repeat(MAX_CONTENT_LENGTH + 1)).validate(), Err(MessageValidationError::ContentTooLong { max: MAX_CONTENT_LENGTH }));
+
+        let embeds = |count| MessageEdit { embeds: Some(vec![embed(); count]), ..Default::default() };
+        assert_eq!(embeds(MAX_EMBEDS).validate(), Ok(()));
+        assert_eq!(embeds(MAX_EMBEDS + 1).validate(), Err(MessageValidationError::TooManyEmbeds { max: MAX_EMBEDS }));
+    }
+}
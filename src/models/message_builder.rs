@@ -0,0 +1,120 @@
+use super::{message::{PartialMessage, Reply, Masquerade, Interactions, ReactionEmoji, MessageValidationError}, embed::SendableEmbed, id::{FileId, MessageId}};
+
+/// Builds a [`PartialMessage`], checking Revolt's limits when it's built
+/// instead of waiting for the server to reject it.
+///
+/// ```no_run
+/// # use harmony::{client::context::Context, models::{message::Message, message_builder::MessageBuilder}};
+/// # async fn run(ctx: &Context, msg: &Message) -> harmony::Result<()> {
+/// let reply = MessageBuilder::new()
+///     .content("pong!")
+///     .reply(&msg.id, false)
+///     .build()?;
+///
+/// ctx.http.send_msg_in_channel(&msg.channel, reply).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MessageBuilder {
+    content: Option<String>,
+    attachments: Vec<FileId>,
+    embeds: Vec<SendableEmbed>,
+    replies: Vec<Reply>,
+    masquerade: Option<Masquerade>,
+    interactions: Option<Interactions>,
+}
+
+impl MessageBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn content(mut self, content: &str) -> Self {
+        self.content = Some(content.to_string());
+
+        self
+    }
+
+    /// Replies to a message, `mention` pings its author
+    pub fn reply(mut self, message_id: &MessageId, mention: bool) -> Self {
+        self.replies.push(Reply {
+            id: message_id.clone(),
+            mention,
+        });
+
+        self
+    }
+
+    pub fn embed(mut self, embed: SendableEmbed) -> Self {
+        self.embeds.push(embed);
+
+        self
+    }
+
+    /// Attaches a file that was uploaded to Autumn, by its id
+    pub fn attachment(mut self, file_id: &FileId) -> Self {
+        self.attachments.push(file_id.clone());
+
+        self
+    }
+
+    pub fn masquerade(mut self, masquerade: Masquerade) -> Self {
+        self.masquerade = Some(masquerade);
+
+        self
+    }
+
+    /// Suggests a reaction below the message, a unicode emoji or a custom emoji's id
+    pub fn reaction(mut self, emoji: impl Into<ReactionEmoji>) -> Self {
+        self.interactions.get_or_insert_with(Interactions::default)
+            .reactions.push(emoji.into().to_string());
+
+        self
+    }
+
+    /// Only allows the suggested reactions to be used
+    pub fn restrict_reactions(mut self, restrict_reactions: bool) -> Self {
+        self.interactions.get_or_insert_with(Interactions::default)
+            .restrict_reactions = restrict_reactions;
+
+        self
+    }
+
+    pub fn build(self) -> Result<PartialMessage, MessageValidationError> {
+        let msg = PartialMessage {
+            content: self.content,
+            attachments: (!self.attachments.is_empty()).then_some(self.attachments),
+            embeds: (!self.embeds.is_empty()).then_some(self.embeds),
+            replies: (!self.replies.is_empty()).then_some(self.replies),
+            masquerade: self.masquerade,
+            interactions: self.interactions,
+        };
+
+        msg.validate()?;
+
+        Ok(msg)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{id::{EmojiId, FileId}, message::ReactionEmoji};
+
+    use super::MessageBuilder;
+
+    #[test]
+    fn builds_typed_attachments_and_reactions() {
+        let emoji_id = EmojiId::new("01GXYZ0000000000000000000A").unwrap();
+
+        let msg = MessageBuilder::new()
+            .attachment(&FileId::new("ymJvsPm3g9GcsLd6Ks6tLUy2uxsMNXNrdZq-GMSpKU"))
+            .reaction(&emoji_id)
+            .reaction(ReactionEmoji::Unicode("👍".to_string()))
+            .build()
+            .unwrap();
+
+        assert_eq!(msg.attachments.unwrap(), ["ymJvsPm3g9GcsLd6Ks6tLUy2uxsMNXNrdZq-GMSpKU"]);
+        assert_eq!(msg.interactions.unwrap().reactions, ["01GXYZ0000000000000000000A", "👍"]);
+    }
+}
@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use bitfield::BitFlag;
+use id::{EmojiId, ServerId, UserId};
+
 pub mod error;
 pub mod user;
 pub mod file;
@@ -8,13 +11,24 @@ pub mod events;
 pub mod server;
 pub mod channel;
 pub mod message;
+pub mod message_builder;
+pub mod content;
+pub mod member;
 pub mod embed;
+pub mod bitfield;
+pub mod id;
+pub mod invite;
+
+/// Used with `skip_serializing_if` to leave out fields that Revolt omits when they're unset
+pub(crate) fn is_default<T: Default + PartialEq>(value: &T) -> bool {
+    *value == T::default()
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "type")]
 pub enum Parent {
     Server {
-        id: String
+        id: ServerId
     },
     Detached
 }
@@ -22,18 +36,18 @@ pub enum Parent {
 #[derive(Debug, Deserialize, Serialize, Clone)]
 pub struct Emoji {
     #[serde(rename = "_id")]
-    id: String,
+    id: EmojiId,
     parent: Parent,
-    creator_id: String,
+    creator_id: UserId,
     name: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     animated: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     nsfw: bool
 }
 
 #[repr(u64)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Permission {
     ManageChannel = 1 << 0,
     ManageServer = 1 << 1,
@@ -79,8 +93,15 @@ pub enum Permission {
     GrantAll = u64::MAX
 }
 
+impl Permission {
+    /// Checks if this permission is granted by the `permissions` bitfield
+    pub fn is_granted(self, permissions: u64) -> bool {
+        permissions & self as u64 == self as u64
+    }
+}
+
 #[repr(i32)]
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Badge {
     Developer = 1,
     Translator = 2,
@@ -95,9 +116,40 @@ pub enum Badge {
     ReservedRelevantJokeBadge2 = 1024,
 }
 
+impl BitFlag for Badge {
+    const ALL: &'static [Self] = &[
+        Self::Developer,
+        Self::Translator,
+        Self::Supporter,
+        Self::ResponsibleDisclosure,
+        Self::Founder,
+        Self::PlatformModeration,
+        Self::ActiveSupporter,
+        Self::Paw,
+        Self::EarlyAdopter,
+        Self::ReservedRelevantJokeBadge1,
+        Self::ReservedRelevantJokeBadge2,
+    ];
+
+    fn bit(self) -> i32 {
+        self as i32
+    }
+}
+
+/// The state of a user's account
+#[repr(i32)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Flag {
     Suspended = 1,
     Deleted = 2,
     Banned = 4,
     Spam = 8,
+}
+
+impl BitFlag for Flag {
+    const ALL: &'static [Self] = &[Self::Suspended, Self::Deleted, Self::Banned, Self::Spam];
+
+    fn bit(self) -> i32 {
+        self as i32
+    }
 }
\ No newline at end of file
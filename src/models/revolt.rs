@@ -1,6 +1,6 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct RevoltInfo {
     #[serde(rename = "revolt")]
     pub api_version: String,
@@ -11,7 +11,7 @@ pub struct RevoltInfo {
     pub build: RevoltBuild
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct RevoltFeatures {
     pub captcha: CaptchaConfig,
     #[serde(rename = "email")]
@@ -22,26 +22,26 @@ pub struct RevoltFeatures {
     pub voso: ServerConfig
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct CaptchaConfig {
     pub enabled: bool,
     pub key: String
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct ServiceConfig {
     pub enabled: bool,
     pub url: String
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct ServerConfig {
     pub enabled: bool,
     pub url: String,
     pub ws: String
 }
 
-#[derive(Debug, Deserialize, Default)]
+#[derive(Debug, Serialize, Deserialize, Default)]
 pub struct RevoltBuild {
     #[serde(default)]
     pub commit_sha: String,
@@ -2,68 +2,162 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use super::file::File;
+use crate::{client::colors::Colour, http::prelude::{HttpClient, HttpError}};
 
-#[derive(Debug, Deserialize, Clone, PartialEq, Default)]
+use super::{channel::{Channel, CreateChannel}, member::MemberList, invite::Invite, file::File, bitfield::{BitFlag, BitFlags}, id::{ChannelId, RoleId, ServerId, UserId}, Permission, is_default};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Server {
     #[serde(rename = "_id")]
-    pub id: String,
-    pub owner: String,
+    pub id: ServerId,
+    pub owner: UserId,
     pub name: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
     #[serde(default)]
-    pub channels: Vec<String>,
-    #[serde(default)]
+    pub channels: Vec<ChannelId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub categories: Vec<ChannelCategory>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub system_messages: Option<SystemMessages>,
-    #[serde(default)]
-    pub roles: HashMap<String, Role>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub roles: HashMap<RoleId, Role>,
     pub default_permissions: i64,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub icon: Option<File>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub banner: Option<File>,
-    #[serde(default)]
-    pub flags: i32,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "BitFlags::is_empty")]
+    pub flags: BitFlags<ServerFlag>,
+    #[serde(default, skip_serializing_if = "is_default")]
     pub nsfw: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     pub analytics: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     pub discoverable: bool,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+impl Server {
+    /// Computes the server-wide permissions of a member with the given roles.
+    ///
+    /// The owner is granted every permission, otherwise the role overrides are
+    /// applied on top of the server's default permissions, from the lowest
+    /// ranking role to the highest.
+    pub fn member_permissions(&self, user_id: &UserId, member_roles: &[RoleId]) -> u64 {
+        if &self.owner == user_id {
+            return Permission::GrantAll as u64;
+        }
+
+        self.roles_by_priority(member_roles)
+            .into_iter()
+            .fold(self.default_permissions as u64, |perms, (_, role)| role.permissions.apply(perms))
+    }
+
+    /// Computes the permissions of a member with the given roles in one of the server's channels.
+    ///
+    /// The channel's default override is applied on top of the member's server-wide permissions,
+    /// followed by the channel's override for each of the member's roles, in the same order as
+    /// [`Server::member_permissions`].
+    pub fn channel_permissions(&self, user_id: &UserId, member_roles: &[RoleId], channel: &Channel) -> u64 {
+        let permissions = self.member_permissions(user_id, member_roles);
+
+        if &self.owner == user_id {
+            return permissions;
+        }
+
+        let (default_permissions, role_permissions) = match channel {
+            Channel::TextChannel(channel) => (channel.get_default_permissions(), channel.get_role_permissions()),
+            Channel::VoiceChannel(channel) => (channel.get_default_permissions(), channel.get_role_permissions()),
+            _ => return permissions,
+        };
+
+        self.roles_by_priority(member_roles)
+            .into_iter()
+            .filter_map(|(id, _)| role_permissions.get(id))
+            .fold(default_permissions.apply(permissions), |perms, role_override| role_override.apply(perms))
+    }
+
+    /// The member's roles ordered from the lowest to the highest priority
+    fn roles_by_priority<'a>(&'a self, member_roles: &'a [RoleId]) -> Vec<(&'a RoleId, &'a Role)> {
+        let mut roles = member_roles.iter()
+            .filter_map(|id| self.roles.get(id).map(|role| (id, role)))
+            .collect::<Vec<_>>();
+
+        // a lower rank means a higher priority, so those are applied last
+        roles.sort_by_key(|(_, role)| std::cmp::Reverse(role.rank));
+
+        roles
+    }
+
+    pub fn is_verified(&self) -> bool {
+        self.flags.contains(ServerFlag::Verified)
+    }
+
+    pub fn is_official(&self) -> bool {
+        self.flags.contains(ServerFlag::Official)
+    }
+}
+
+impl Server {
+    pub async fn create_channel(&self, ctx: &impl AsRef<HttpClient>, channel: CreateChannel) -> Result<Channel, HttpError> {
+        ctx.as_ref().create_channel(&self.id, channel).await
+    }
+
+    /// Fetches every member of the server, along with their users
+    pub async fn fetch_members(&self, ctx: &impl AsRef<HttpClient>) -> Result<MemberList, HttpError> {
+        ctx.as_ref().get_members(&self.id).await
+    }
+
+    pub async fn fetch_invites(&self, ctx: &impl AsRef<HttpClient>) -> Result<Vec<Invite>, HttpError> {
+        ctx.as_ref().get_invites(&self.id).await
+    }
+}
+
+/// Badges shown next to a server's name
+#[repr(i32)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ServerFlag {
+    Verified = 1,
+    Official = 2,
+}
+
+impl BitFlag for ServerFlag {
+    const ALL: &'static [Self] = &[Self::Verified, Self::Official];
+
+    fn bit(self) -> i32 {
+        self as i32
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct PartialServer {
-    #[serde(default)]
-    pub owner: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub owner: Option<UserId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub description: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub categories: Option<Vec<ChannelCategory>>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub system_messages: Option<SystemMessages>,
-    #[serde(default)]
-    pub flags: Option<i32>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub flags: Option<BitFlags<ServerFlag>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub nsfw: Option<bool>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub analytics: Option<bool>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub discoverable: Option<bool>,
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Role {
     pub name: String,
     pub permissions: PermissionOverride,
-    #[serde(default)]
-    pub colour: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub colour: Option<Colour>,
+    #[serde(default, skip_serializing_if = "is_default")]
     pub hoist: bool,
     #[serde(default)]
     pub rank: i32,
@@ -79,23 +173,130 @@ pub struct PermissionOverride {
     deny: i64
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+impl PermissionOverride {
+    pub fn get_allow(&self) -> i64 {
+        self.allow
+    }
+
+    pub fn get_deny(&self) -> i64 {
+        self.deny
+    }
+
+    /// Applies this override on top of the `permissions` bitfield
+    pub fn apply(&self, permissions: u64) -> u64 {
+        (permissions | self.allow as u64) & !(self.deny as u64)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct SystemMessages {
-    #[serde(default)]
-    pub user_joined: Option<String>,
-    #[serde(default)]
-    pub user_left: Option<String>,
-    #[serde(default)]
-    pub user_kicked: Option<String>,
-    #[serde(default)]
-    pub user_banned: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_joined: Option<ChannelId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_left: Option<ChannelId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_kicked: Option<ChannelId>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_banned: Option<ChannelId>,
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct ChannelCategory {
     pub id: String,
     pub title: String,
     #[serde(default)]
-    pub channels: Vec<String>,
+    pub channels: Vec<ChannelId>,
 
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::models::{channel::Channel, id::{RoleId, UserId}, Permission};
+
+    use super::Server;
+
+    const OWNER: &str = "01FD58YK5W7QRV5H3D64KTQYX3";
+    const MEMBER: &str = "01FD58YK5W7QRV5H3D64KTQYX4";
+    const HIGH_ROLE: &str = "01H2X8W9J3K5M7N9P1Q3R5S7T9";
+    const LOW_ROLE: &str = "01H2X8W9J3K5M7N9P1Q3R5S7TA";
+
+    const VIEW: u64 = Permission::ViewChannel as u64;
+    const SEND: u64 = Permission::SendMessage as u64;
+
+    /// A server whose higher ranking role allows sending messages, while the lower one denies it
+    fn server() -> Server {
+        serde_json::from_value(json!({
+            "_id": "01F7ZSBSFHQ8TA81725KQCSDDP",
+            "owner": OWNER,
+            "name": "Testers",
+            "roles": {
+                HIGH_ROLE: { "name": "High", "permissions": { "a": SEND, "d": 0 }, "rank": 0 },
+                LOW_ROLE: { "name": "Low", "permissions": { "a": 0, "d": SEND }, "rank": 1 },
+            },
+            "default_permissions": VIEW,
+        })).unwrap()
+    }
+
+    fn channel(role_permissions: serde_json::Value) -> Channel {
+        serde_json::from_value(json!({
+            "channel_type": "TextChannel",
+            "_id": "01H2X8V3YJ5QK2W7D9R6T4B1CA",
+            "server": "01F7ZSBSFHQ8TA81725KQCSDDP",
+            "name": "general",
+            "default_permissions": { "a": 0, "d": VIEW },
+            "role_permissions": role_permissions,
+        })).unwrap()
+    }
+
+    fn roles(ids: &[&str]) -> Vec<RoleId> {
+        ids.iter().map(|id| id.parse().unwrap()).collect()
+    }
+
+    #[test]
+    fn applies_higher_ranking_roles_last() {
+        let server = server();
+        let member = MEMBER.parse::<UserId>().unwrap();
+
+        assert_eq!(server.member_permissions(&member, &[]), VIEW);
+        assert_eq!(server.member_permissions(&member, &roles(&[LOW_ROLE])), VIEW);
+        // the order the member lists their roles in doesn't matter
+        assert_eq!(server.member_permissions(&member, &roles(&[HIGH_ROLE, LOW_ROLE])), VIEW | SEND);
+        assert_eq!(server.member_permissions(&member, &roles(&[LOW_ROLE, HIGH_ROLE])), VIEW | SEND);
+    }
+
+    #[test]
+    fn applies_channel_overrides_by_rank() {
+        let server = server();
+        let member = MEMBER.parse::<UserId>().unwrap();
+        let member_roles = roles(&[HIGH_ROLE, LOW_ROLE]);
+
+        let channel = channel(json!({
+            HIGH_ROLE: { "a": VIEW, "d": 0 },
+            LOW_ROLE: { "a": 0, "d": VIEW },
+        }));
+
+        assert_eq!(server.channel_permissions(&member, &[], &channel), 0);
+        assert_eq!(server.channel_permissions(&member, &roles(&[LOW_ROLE]), &channel), 0);
+        assert_eq!(server.channel_permissions(&member, &member_roles, &channel), VIEW | SEND);
+
+        let channel = channel_with_swapped_overrides();
+        assert_eq!(server.channel_permissions(&member, &member_roles, &channel), SEND);
+    }
+
+    fn channel_with_swapped_overrides() -> Channel {
+        channel(json!({
+            HIGH_ROLE: { "a": 0, "d": VIEW },
+            LOW_ROLE: { "a": VIEW, "d": 0 },
+        }))
+    }
+
+    #[test]
+    fn owner_bypasses_every_override() {
+        let server = server();
+        let owner = OWNER.parse::<UserId>().unwrap();
+
+        assert_eq!(server.member_permissions(&owner, &roles(&[LOW_ROLE])), Permission::GrantAll as u64);
+        assert_eq!(server.channel_permissions(&owner, &roles(&[LOW_ROLE]), &channel_with_swapped_overrides()), Permission::GrantAll as u64);
+    }
+}
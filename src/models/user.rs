@@ -1,33 +1,32 @@
 use serde::{Deserialize, Serialize};
 
-use super::{file::File, Badge};
+use crate::http::prelude::{HttpClient, HttpError};
 
-#[derive(Debug, Deserialize, Clone, PartialEq, Default)]
+use super::{channel::Channel, file::File, bitfield::BitFlags, id::UserId, events::server::{PartialUser, UserClear}, Badge, Flag, is_default};
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct User {
     #[serde(rename = "_id")]
-    pub id: String,
+    pub id: UserId,
     pub username: String,
     pub discriminator: String,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub display_name: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub avatar: Option<File>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub relations: Vec<Relationship>,
-    #[serde(rename = "badges")]
-    #[serde(default)]
-    pub badges_bitfield: i32,
-    #[serde(skip)]
-    pub badges: Vec<Badge>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "BitFlags::is_empty")]
+    pub badges: BitFlags<Badge>,
+    #[serde(default, skip_serializing_if = "is_default")]
     pub status: Status,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "is_default")]
     pub profile: UserProfile,
-    #[serde(default)]
-    pub flags: i32,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "BitFlags::is_empty")]
+    pub flags: BitFlags<Flag>,
+    #[serde(default, skip_serializing_if = "is_default")]
     pub privileged: bool,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub bot: Option<BotInfo>,
     #[serde(default)]
     pub relationship: RelationshipStatus,
@@ -35,36 +34,149 @@ pub struct User {
     pub online: bool,
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+impl User {
+    /// The user's display name, or their username if they haven't set one
+    pub fn get_display_name(&self) -> String {
+        self.display_name.clone()
+            .unwrap_or_else(|| self.username.clone())
+    }
+
+    /// Applies a `UserUpdate` to this user
+    pub fn apply(&mut self, data: PartialUser, clear: &[UserClear]) {
+        for field in clear {
+            match field {
+                UserClear::ProfileContent => self.profile.content = None,
+                UserClear::ProfileBackground => self.profile.background = None,
+                UserClear::StatusText => self.status.text = None,
+                UserClear::StatusPresence => self.status.presence = None,
+                UserClear::Avatar => self.avatar = None,
+                UserClear::DisplayName => self.display_name = None,
+            }
+        }
+
+        if let Some(username) = data.username {
+            self.username = username;
+        }
+
+        if let Some(discriminator) = data.discriminator {
+            self.discriminator = discriminator;
+        }
+
+        if let Some(display_name) = data.display_name {
+            self.display_name = Some(display_name);
+        }
+
+        if let Some(avatar) = data.avatar {
+            self.avatar = Some(avatar);
+        }
+
+        if let Some(status) = data.status {
+            self.status.apply(status);
+        }
+
+        if let Some(profile) = data.profile {
+            self.profile = profile;
+        }
+
+        if let Some(badges) = data.badges {
+            self.badges = badges;
+        }
+
+        if let Some(flags) = data.flags {
+            self.flags = flags;
+        }
+
+        if let Some(online) = data.online {
+            self.online = online;
+        }
+    }
+
+    pub fn has_badge(&self, badge: Badge) -> bool {
+        self.badges.contains(badge)
+    }
+
+    pub fn has_flag(&self, flag: Flag) -> bool {
+        self.flags.contains(flag)
+    }
+
+    pub fn is_suspended(&self) -> bool {
+        self.has_flag(Flag::Suspended)
+    }
+
+    pub fn is_deleted(&self) -> bool {
+        self.has_flag(Flag::Deleted)
+    }
+
+    pub fn is_banned(&self) -> bool {
+        self.has_flag(Flag::Banned)
+    }
+}
+
+impl User {
+    /// Opens the DM channel with this user, creating it if needed
+    pub async fn dm(&self, ctx: &impl AsRef<HttpClient>) -> Result<Channel, HttpError> {
+        ctx.as_ref().open_dm(&self.id).await
+    }
+
+    /// Fetches the user's profile, which isn't sent along with the user
+    pub async fn fetch_profile(&self, ctx: &impl AsRef<HttpClient>) -> Result<UserProfile, HttpError> {
+        ctx.as_ref().get_user_profile(&self.id).await
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct Relationship {
     #[serde(rename = "_id")]
-    pub id: String,
+    pub id: UserId,
     #[serde(default)]
     pub status: RelationshipStatus
 }
 
-#[derive(Debug, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
 pub struct BotInfo {
-    pub owner: String
+    pub owner: UserId
 }
 
-#[derive(Debug, Deserialize, Default, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
 pub struct UserProfile {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub content: Option<String>,
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub background: Option<File>
 }
 
-#[derive(Debug, Deserialize, Default, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
 pub struct Status {
-    #[serde(default)]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub text: Option<String>,
-    #[serde(default)]
-    pub presence: Presence,
+    /// `None` if the user hasn't chosen a presence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence: Option<Presence>,
+}
+
+impl Status {
+    /// Applies the fields set in a status update, keeping the others
+    pub fn apply(&mut self, data: PartialStatus) {
+        if let Some(text) = data.text {
+            self.text = Some(text);
+        }
+
+        if let Some(presence) = data.presence {
+            self.presence = Some(presence);
+        }
+    }
+}
+
+/// The fields of a [`Status`] changed by a `UserUpdate`
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
+pub struct PartialStatus {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub presence: Option<Presence>,
 }
 
-#[derive(Debug, Deserialize, Default, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
 pub enum Presence {
     Online,
     Idle,
@@ -74,7 +186,7 @@ pub enum Presence {
     Invisible
 }
 
-#[derive(Debug, Deserialize, Default, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone, PartialEq)]
 pub enum RelationshipStatus {
     #[default]
     None,
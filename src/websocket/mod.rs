@@ -1,11 +1,11 @@
-use std::{fmt::Display, time::{Duration, Instant}};
+use std::{fmt::Display, sync::{Arc, Mutex}, time::{Duration, Instant}};
 
 use async_channel::{Receiver, Sender};
 use futures_util::{StreamExt, SinkExt};
 use tokio::{net::TcpStream, select, time::sleep};
 use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream, tungstenite::Message};
 
-use crate::models::events::{server::ServerEvent, client::ClientEvent};
+use crate::models::{events::{server::ServerEvent, client::ClientEvent}, id::ChannelId};
 
 /// the default Revolt websocket url
 pub const REVOLT_WEBSOCKET_URL: &str = "wss://ws.revolt.chat";
@@ -29,6 +29,8 @@ pub struct SocketClient {
     pub ws: String,
     pub heartbeat_interval: u64,
     pub last_heartbeat: Instant,
+    /// When the last heartbeat was sent
+    pub last_ping: Arc<Mutex<Option<Instant>>>,
     
     pub client_sender: Sender<ClientEvent>,
     pub client_receiver: Receiver<ClientEvent>,
@@ -41,13 +43,25 @@ pub struct SocketClient {
 
 type SocketResult<T> = Result<T, SocketError>;
 
-#[derive(Debug)]
+/// Represents an error that occurred within Harmony's websocket client
+#[derive(Debug, thiserror::Error)]
 pub enum SocketError {
-    JsonSerializationError(serde_json::error::Error),
-    TungsteniteError(tokio_tungstenite::tungstenite::error::Error),
+    #[error("Could not (de)serialize event: {0}")]
+    JsonSerializationError(#[from] serde_json::error::Error),
+    #[error("Websocket error: {0}")]
+    TungsteniteError(#[from] tokio_tungstenite::tungstenite::error::Error),
+    #[error("Could not send event, the connection is closed")]
     SendError,
 }
 
+impl SocketError {
+    /// Checks if the error came from the connection rather than a malformed event,
+    /// meaning reconnecting may succeed
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::TungsteniteError(_) | Self::SendError)
+    }
+}
+
 impl SocketClient {
     async fn handle_events(
         mut client_receiver: Receiver<ClientEvent>,
@@ -56,11 +70,27 @@ impl SocketClient {
     ) {
         loop {
             select! {
-                Some(event) = client_receiver.next() => {
+                event = client_receiver.next() => {
+                    let event = match event {
+                        Some(event) => event,
+                        None => {
+                            // the client has been closed
+                            tracing::info!("closing websocket connection");
+
+                            let _ = socket.close(None).await;
+                            break;
+                        }
+                    };
+
                     match serde_json::to_string(&event) {
                         Ok(data) => {
                             let msg = Message::Text(data);
-                            socket.send(msg).await.unwrap();
+
+                            if let Err(err) = socket.send(msg).await {
+                                tracing::warn!(%err, "could not send event");
+
+                                let _ = server_sender.send(Err(SocketError::TungsteniteError(err))).await;
+                            }
                         },
                         Err(json_err) => {
                             if let Err(e) = server_sender.send(Err(SocketError::JsonSerializationError(json_err))).await {
@@ -79,6 +109,8 @@ impl SocketClient {
                                             Some(event)
                                         },
                                         Err(json_err) => {
+                                            tracing::warn!(%json_err, "could not deserialize event");
+
                                             let res = server_sender.send(Err(SocketError::JsonSerializationError(json_err))).await;
                                             
                                             if let Err(e) = res {
@@ -91,19 +123,23 @@ impl SocketClient {
 
                                     event
                                 },
+                                Message::Close(frame) => {
+                                    tracing::info!(?frame, "websocket closed by the server");
+                                    None
+                                },
                                 _ => {
-                                    println!("Unhandled Event");
+                                    tracing::trace!("ignoring non-text websocket message");
                                     None
                                 }
                             };
         
                             if let Some(event) = event {
                                 server_sender.send(Ok(event)).await.unwrap();
-                            } else {
-
                             }
                         },
                         Err(msg_err) => {
+                            tracing::warn!(%msg_err, "websocket error");
+
                             if let Err(e) = server_sender.send(Err(SocketError::TungsteniteError(msg_err))).await {
                                 panic!("An error occurred while sending result: {e:#?}")
                             }
@@ -113,12 +149,14 @@ impl SocketClient {
                 else => break
             };
         }
+
+        tracing::info!("websocket connection closed");
     }
 
-    pub async fn connect(config: ClientConfig) -> Self {
-        let (stream, _) = connect_async(config.ws.clone())
-            .await
-            .expect("cant connect");
+    pub async fn connect(config: ClientConfig) -> Result<Self, SocketError> {
+        let (stream, _) = connect_async(config.ws.clone()).await?;
+
+        tracing::info!(url = %config.ws, "connected to websocket");
 
         let (client_sender, client_receiver) = async_channel::unbounded();
         let (server_sender, server_receiver) = async_channel::unbounded();
@@ -127,22 +165,36 @@ impl SocketClient {
 
         let dummy = Instant::now() - Duration::from_secs(config.heartbeat_interval);
 
-        tokio::spawn(SocketClient::heartbeat(client_sender.clone(), config.heartbeat_interval));
+        let last_ping = Arc::new(Mutex::new(None));
+
+        tokio::spawn(SocketClient::heartbeat(client_sender.clone(), config.heartbeat_interval, last_ping.clone()));
         tokio::spawn(SocketClient::handle_events(client_receiver.clone(), server_sender.clone(), stream));
 
-        Self {
+        Ok(Self {
             ws: config.ws,
             heartbeat_interval: config.heartbeat_interval,
             last_heartbeat: dummy,
+            last_ping,
             
-            client_sender: client_sender,
-            client_receiver: client_receiver,
+            client_sender,
+            client_receiver,
             
-            server_sender: server_sender,
-            server_receiver: server_receiver,
+            server_sender,
+            server_receiver,
 
             //socket
-        }
+        })
+    }
+
+    /// Opens a new connection to the same websocket, e.g. after the server closed this one.
+    /// This connection is closed first
+    pub async fn reconnect(&self) -> Result<Self, SocketError> {
+        self.close();
+
+        Self::connect(ClientConfig {
+            ws: self.ws.clone(),
+            heartbeat_interval: self.heartbeat_interval,
+        }).await
     }
 
     pub async fn send(&mut self, event: ClientEvent) -> Result<(), SocketError> {
@@ -153,21 +205,36 @@ impl SocketClient {
         Ok(())
     }
 
+    /// Closes the connection to the websocket
+    pub fn close(&self) {
+        self.client_sender.close();
+    }
+
     pub async fn authenticate(&mut self, token: &str) -> Result<(), SocketError> {
         self.send(ClientEvent::Authenticate { token: token.to_string() }).await
     }
 
-    pub async fn start_typing(&mut self, channel_id: &str) -> Result<(), SocketError> {
-        self.send(ClientEvent::BeginTyping { channel_id: channel_id.to_string() }).await
+    pub async fn start_typing(&mut self, channel_id: &ChannelId) -> Result<(), SocketError> {
+        self.send(ClientEvent::BeginTyping { channel_id: channel_id.clone() }).await
     }
 
-    pub async fn stop_typing(&mut self, channel_id: &str) -> Result<(), SocketError> {
-        self.send(ClientEvent::EndTyping { channel_id: channel_id.to_string() }).await
+    pub async fn stop_typing(&mut self, channel_id: &ChannelId) -> Result<(), SocketError> {
+        self.send(ClientEvent::EndTyping { channel_id: channel_id.clone() }).await
     }
 
-    async fn heartbeat(client_sender: Sender<ClientEvent>, heartbeat_interval: u64) {
+    /// The time since the last heartbeat was sent, used to measure latency when its `Pong` arrives
+    pub fn since_last_ping(&self) -> Option<Duration> {
+        self.last_ping.lock().unwrap().map(|sent| sent.elapsed())
+    }
+
+    async fn heartbeat(client_sender: Sender<ClientEvent>, heartbeat_interval: u64, last_ping: Arc<Mutex<Option<Instant>>>) {
         loop {
-            client_sender.send(ClientEvent::Ping { data: 0 }).await.unwrap();
+            if client_sender.send(ClientEvent::Ping { data: 0 }).await.is_err() {
+                break;
+            }
+
+            *last_ping.lock().unwrap() = Some(Instant::now());
+
             sleep(Duration::from_secs(heartbeat_interval)).await;
         }
     }
@@ -185,6 +252,12 @@ pub struct ClientConfigBuilder {
     heartbeat_interval: u64,
 }
 
+impl Default for ClientConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ClientConfigBuilder {
     pub fn new() -> Self {
         Self {
@@ -0,0 +1,74 @@
+//! Checks that models serialize back to exactly what Revolt sent, using the payloads in `fixtures/`
+
+use harmony::models::{channel::Channel, events::server::ServerEvent, invite::{Invite, InviteInfo}, member::Member, message::Message, server::Server, user::User};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+
+fn assert_round_trip<T: DeserializeOwned + Serialize>(fixture: &str) {
+    let model = serde_json::from_str::<T>(fixture).expect("the fixture should deserialize");
+
+    let expected = serde_json::from_str::<Value>(fixture).unwrap();
+    let actual = serde_json::to_value(&model).unwrap();
+
+    assert_eq!(actual, expected);
+}
+
+macro_rules! round_trip {
+    ($($name:ident: $ty:ty => $fixture:literal),* $(,)?) => {
+        $(
+            #[test]
+            fn $name() {
+                assert_round_trip::<$ty>(include_str!(concat!("fixtures/", $fixture)));
+            }
+        )*
+    };
+}
+
+round_trip! {
+    message: Message => "message.json",
+    user: User => "user.json",
+    bot_user: User => "bot_user.json",
+    server: Server => "server.json",
+    text_channel: Channel => "text_channel.json",
+    voice_channel: Channel => "voice_channel.json",
+    dm_channel: Channel => "dm_channel.json",
+    group_channel: Channel => "group_channel.json",
+    saved_messages_channel: Channel => "saved_messages_channel.json",
+    member: Member => "member.json",
+    server_invite: Invite => "server_invite.json",
+    group_invite: Invite => "group_invite.json",
+    server_invite_info: InviteInfo => "server_invite_info.json",
+    group_invite_info: InviteInfo => "group_invite_info.json",
+    message_event: ServerEvent => "message_event.json",
+    message_update_event: ServerEvent => "message_update_event.json",
+    user_update_event: ServerEvent => "user_update_event.json",
+    member_update_event: ServerEvent => "member_update_event.json",
+    ready_event: ServerEvent => "ready_event.json",
+}
+
+#[test]
+fn reads_invites() {
+    let invite: Invite = serde_json::from_str(include_str!("fixtures/server_invite.json")).unwrap();
+
+    assert_eq!(invite.get_code(), "Testers");
+    assert_eq!(invite.get_url(), "https://rvlt.gg/Testers");
+    assert_eq!(invite.get_creator(), "01FD58YK5W7QRV5H3D64KTQYX3");
+    assert_eq!(invite.get_channel_id(), "01H2X8V3YJ5QK2W7D9R6T4B1CA");
+}
+
+#[test]
+fn reads_invite_info() {
+    let server: InviteInfo = serde_json::from_str(include_str!("fixtures/server_invite_info.json")).unwrap();
+
+    assert_eq!(server.get_code(), "Testers");
+    assert_eq!(server.get_server_name(), Some("Revolt Testers"));
+    assert_eq!(server.get_channel_name(), "general");
+    assert_eq!(server.get_member_count(), Some(18452));
+
+    let group: InviteInfo = serde_json::from_str(include_str!("fixtures/group_invite_info.json")).unwrap();
+
+    assert_eq!(group.get_code(), "c8YnUyAx");
+    assert_eq!(group.get_server_name(), None);
+    assert_eq!(group.get_channel_name(), "Weekend plans");
+    assert_eq!(group.get_member_count(), None);
+}